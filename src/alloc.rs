@@ -0,0 +1,168 @@
+use serde::Serialize;
+
+/// What was allocated while running something, see [`measure`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AllocStats {
+	pub allocations: u64,
+	pub bytes: u64,
+	/// The most memory that was allocated at once, on top of what was allocated before.
+	pub peak_bytes: u64,
+}
+
+/// Runs `function` and counts the allocations of every thread while it runs, which is only
+/// possible with the `alloc-stats` feature.
+pub fn measure<T>(function: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+	#[cfg(feature = "alloc-stats")]
+	{
+		let (result, stats) = counting::measure(&counting::GLOBAL, function);
+		(result, Some(stats))
+	}
+	#[cfg(not(feature = "alloc-stats"))]
+	{
+		(function(), None)
+	}
+}
+
+/// Like [`measure`], but only counts the allocations of the current thread, so other threads
+/// (like the ones of other tests) don't get in the way.
+pub fn measure_thread<T>(function: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+	#[cfg(feature = "alloc-stats")]
+	{
+		let (result, stats) =
+			counting::THREAD.with(|counters| counting::measure(counters, function));
+		(result, Some(stats))
+	}
+	#[cfg(not(feature = "alloc-stats"))]
+	{
+		(function(), None)
+	}
+}
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+	use std::alloc::{GlobalAlloc, Layout, System};
+	use std::sync::atomic::{AtomicI64, AtomicU64, Ordering::Relaxed};
+
+	use super::AllocStats;
+
+	pub struct Counters {
+		allocations: AtomicU64,
+		bytes: AtomicU64,
+		// memory freed by another thread can make a thread's current usage negative
+		current: AtomicI64,
+		peak: AtomicI64,
+	}
+
+	impl Counters {
+		const fn new() -> Self {
+			Self {
+				allocations: AtomicU64::new(0),
+				bytes: AtomicU64::new(0),
+				current: AtomicI64::new(0),
+				peak: AtomicI64::new(0),
+			}
+		}
+
+		fn allocated(&self, size: usize) {
+			self.allocations.fetch_add(1, Relaxed);
+			self.bytes.fetch_add(size as u64, Relaxed);
+			let current = self.current.fetch_add(size as i64, Relaxed) + size as i64;
+			self.peak.fetch_max(current, Relaxed);
+		}
+
+		fn freed(&self, size: usize) {
+			self.current.fetch_sub(size as i64, Relaxed);
+		}
+	}
+
+	pub static GLOBAL: Counters = Counters::new();
+
+	thread_local! {
+		pub static THREAD: Counters = const { Counters::new() };
+	}
+
+	pub fn measure<T>(counters: &Counters, function: impl FnOnce() -> T) -> (T, AllocStats) {
+		let allocations = counters.allocations.load(Relaxed);
+		let bytes = counters.bytes.load(Relaxed);
+		let current = counters.current.load(Relaxed);
+		counters.peak.store(current, Relaxed);
+
+		let result = function();
+
+		let stats = AllocStats {
+			allocations: counters.allocations.load(Relaxed) - allocations,
+			bytes: counters.bytes.load(Relaxed) - bytes,
+			peak_bytes: (counters.peak.load(Relaxed) - current).max(0) as u64,
+		};
+		(result, stats)
+	}
+
+	struct CountingAllocator;
+
+	fn count(update: impl Fn(&Counters)) {
+		update(&GLOBAL);
+		// the thread local is gone while a thread shuts down
+		let _ = THREAD.try_with(update);
+	}
+
+	unsafe impl GlobalAlloc for CountingAllocator {
+		unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+			let pointer = System.alloc(layout);
+			if !pointer.is_null() {
+				count(|counters| counters.allocated(layout.size()));
+			}
+			pointer
+		}
+
+		unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+			let pointer = System.alloc_zeroed(layout);
+			if !pointer.is_null() {
+				count(|counters| counters.allocated(layout.size()));
+			}
+			pointer
+		}
+
+		unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+			System.dealloc(pointer, layout);
+			count(|counters| counters.freed(layout.size()));
+		}
+
+		unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+			let new_pointer = System.realloc(pointer, layout, new_size);
+			if !new_pointer.is_null() {
+				count(|counters| {
+					counters.freed(layout.size());
+					counters.allocated(new_size);
+				});
+			}
+			new_pointer
+		}
+	}
+
+	#[global_allocator]
+	static ALLOCATOR: CountingAllocator = CountingAllocator;
+}
+
+#[cfg(all(test, feature = "alloc-stats"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn allocations_are_counted() {
+		let (vector, stats) = measure_thread(|| {
+			let mut vector = Vec::<u64>::with_capacity(16);
+			drop(Vec::<u8>::with_capacity(1024));
+			vector.push(1);
+			vector
+		});
+		assert_eq!(vector, [1]);
+		assert_eq!(
+			stats,
+			Some(AllocStats {
+				allocations: 2,
+				bytes: 16 * 8 + 1024,
+				peak_bytes: 16 * 8 + 1024,
+			})
+		);
+	}
+}
@@ -0,0 +1,15 @@
+use std::fs;
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::Answers;
+
+pub fn path(year: usize, day: usize) -> String {
+	format!("answers/{year}/day-{day:02}.toml")
+}
+
+pub fn load(year: usize, day: usize) -> Result<Answers> {
+	let path = path(year, day);
+	let text = fs::read_to_string(&path).wrap_err_with(|| format!("failed to read {path}"))?;
+	toml::from_str(&text).wrap_err_with(|| format!("failed to parse {path}"))
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::runner::catch_panic;
+use crate::Solution;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+	pub min_nanos: u64,
+	pub median_nanos: u64,
+	pub p95_nanos: u64,
+}
+
+impl Stats {
+	pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+		assert!(!samples.is_empty(), "there has to be at least one sample");
+		samples.sort();
+		// nearest rank, so every statistic is one of the samples
+		let percentile = |percent: usize| {
+			let rank = (percent * samples.len()).div_ceil(100).max(1);
+			samples[rank - 1].as_nanos() as u64
+		};
+		Self {
+			min_nanos: percentile(0),
+			median_nanos: percentile(50),
+			p95_nanos: percentile(95),
+		}
+	}
+
+	/// How much slower (positive) or faster (negative) the median got, relative to `baseline`.
+	pub fn change(&self, baseline: &Stats) -> f64 {
+		self.median_nanos as f64 / baseline.median_nanos.max(1) as f64 - 1.0
+	}
+}
+
+/// The statistics of a day, by step (`parse`, `part 1` and `part 2`).
+pub struct DayBench {
+	pub year: usize,
+	pub day: usize,
+	pub steps: Vec<(String, Stats)>,
+}
+
+impl DayBench {
+	pub fn name(&self) -> String {
+		format!("{}/{}", self.year, self.day)
+	}
+}
+
+fn sample<T>(warmup: usize, runs: usize, mut function: impl FnMut() -> Result<T>) -> Result<Stats> {
+	for _ in 0..warmup {
+		black_box(catch_panic(&mut function)?);
+	}
+	let mut samples = Vec::with_capacity(runs);
+	for _ in 0..runs {
+		let start = Instant::now();
+		let result = black_box(catch_panic(&mut function));
+		samples.push(start.elapsed());
+		result?;
+	}
+	Ok(Stats::from_samples(samples))
+}
+
+/// Times parsing and the (selected) parts `runs` times each, after `warmup` untimed runs. The
+/// parts all run on the same parsed input, so only the computation itself is measured.
+pub fn measure(
+	solution: &Solution,
+	input: &str,
+	warmup: usize,
+	runs: usize,
+	selected_part: Option<usize>,
+) -> Result<DayBench> {
+	if runs == 0 {
+		return Err(eyre!("there has to be at least one run"));
+	}
+
+	let mut steps = vec![(
+		"parse".to_string(),
+		sample(warmup, runs, || (solution.parse)(input))?,
+	)];
+	let parsed = (solution.parse)(input)?;
+	for (number, part) in solution.parts() {
+		if selected_part.is_none_or(|selected| selected == number) {
+			let stats = sample(warmup, runs, || part(&parsed))
+				.wrap_err_with(|| format!("part {number} failed"))?;
+			steps.push((format!("part {number}"), stats));
+		}
+	}
+
+	Ok(DayBench {
+		year: solution.year,
+		day: solution.day,
+		steps,
+	})
+}
+
+/// Saved statistics by day (like `2022/17`) and step.
+pub type Baseline = BTreeMap<String, BTreeMap<String, Stats>>;
+
+pub fn load_baseline(path: &Path) -> Result<Baseline> {
+	let text =
+		fs::read_to_string(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+	serde_json::from_str(&text).wrap_err_with(|| format!("failed to parse {}", path.display()))
+}
+
+/// Adds the benchmarks to the baseline at `path`, replacing the steps that were measured again.
+pub fn save_baseline(path: &Path, benches: &[DayBench]) -> Result<()> {
+	let mut baseline = if path.exists() {
+		load_baseline(path)?
+	} else {
+		Baseline::new()
+	};
+	for bench in benches {
+		let steps = baseline.entry(bench.name()).or_default();
+		steps.extend(bench.steps.iter().cloned());
+	}
+	let text = serde_json::to_string_pretty(&baseline)?;
+	fs::write(path, text + "\n").wrap_err_with(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::find_solution;
+
+	#[test]
+	fn statistics_are_nearest_ranks() {
+		let samples = (1..=20).rev().map(Duration::from_micros).collect();
+		let stats = Stats::from_samples(samples);
+		assert_eq!(stats.min_nanos, 1_000);
+		assert_eq!(stats.median_nanos, 10_000);
+		assert_eq!(stats.p95_nanos, 19_000);
+
+		let slower = Stats {
+			median_nanos: 12_500,
+			..stats
+		};
+		assert_eq!(slower.change(&stats), 0.25);
+		assert!((stats.change(&slower) + 0.2).abs() < 1e-9);
+	}
+
+	#[test]
+	fn every_step_is_measured() -> Result<()> {
+		let solution = find_solution(2022, 6)?;
+		let bench = measure(solution, "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n", 1, 3, None)?;
+		let steps: Vec<_> = bench.steps.iter().map(|(step, _)| step.as_str()).collect();
+		assert_eq!(steps, ["parse", "part 1", "part 2"]);
+
+		let bench = measure(solution, "abc", 0, 3, Some(1));
+		assert!(bench.is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn baselines_are_merged() -> Result<()> {
+		let directory = tempfile::tempdir()?;
+		let path = directory.path().join("baseline.json");
+		let stats = |nanos| Stats {
+			min_nanos: nanos,
+			median_nanos: nanos,
+			p95_nanos: nanos,
+		};
+		let bench = |day, steps: &[(&str, u64)]| DayBench {
+			year: 2022,
+			day,
+			steps: steps
+				.iter()
+				.map(|&(step, nanos)| (step.to_string(), stats(nanos)))
+				.collect(),
+		};
+
+		save_baseline(&path, &[bench(1, &[("parse", 1), ("part 1", 2)])])?;
+		save_baseline(
+			&path,
+			&[bench(1, &[("part 1", 3)]), bench(2, &[("parse", 4)])],
+		)?;
+		let baseline = load_baseline(&path)?;
+		assert_eq!(baseline["2022/1"]["parse"], stats(1));
+		assert_eq!(baseline["2022/1"]["part 1"], stats(3));
+		assert_eq!(baseline["2022/2"]["parse"], stats(4));
+		Ok(())
+	}
+}
@@ -0,0 +1,117 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+
+/// Returned by [`CancellationToken::check`] once the token was cancelled.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("cancelled")
+	}
+}
+
+impl Error for Cancelled {}
+
+/// Lets long-running loops give up early. Clones share the same state, so a token can be handed
+/// to other threads (like rayon's).
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+thread_local! {
+	static CURRENT: RefCell<CancellationToken> = RefCell::default();
+}
+
+impl CancellationToken {
+	/// The token of the part running on this thread, see [`CancellationToken::scope`]. Outside
+	/// of one (like in tests) the token is never cancelled.
+	pub fn current() -> Self {
+		CURRENT.with(|current| current.borrow().clone())
+	}
+
+	/// Runs `function` with this as the [current](CancellationToken::current) token.
+	pub fn scope<T>(&self, function: impl FnOnce() -> T) -> T {
+		let previous = CURRENT.with(|current| current.replace(self.clone()));
+		// restores the previous token even if `function` panics
+		struct Restore(Option<CancellationToken>);
+		impl Drop for Restore {
+			fn drop(&mut self) {
+				let previous = self.0.take().expect("restored once");
+				CURRENT.with(|current| *current.borrow_mut() = previous);
+			}
+		}
+		let _restore = Restore(Some(previous));
+		function()
+	}
+
+	/// Runs `function` in [`scope`](CancellationToken::scope) of a new token that is cancelled
+	/// once `timeout` passes, or never without a timeout.
+	pub fn with_timeout<T>(timeout: Option<Duration>, function: impl FnOnce() -> T) -> T {
+		let token = CancellationToken::default();
+		let Some(timeout) = timeout else {
+			return token.scope(function);
+		};
+
+		// the watchdog either times out or wakes up early when `done` is dropped
+		let (done, finished) = mpsc::channel::<()>();
+		let watchdog = {
+			let token = token.clone();
+			thread::spawn(move || {
+				if let Err(mpsc::RecvTimeoutError::Timeout) = finished.recv_timeout(timeout) {
+					token.cancel();
+				}
+			})
+		};
+		let result = token.scope(function);
+		drop(done);
+		watchdog.join().expect("the watchdog doesn't panic");
+		result
+	}
+
+	pub fn cancel(&self) {
+		self.0.store(true, Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Relaxed)
+	}
+
+	/// Fails with [`Cancelled`] once the token was cancelled, cheap enough for any loop.
+	pub fn check(&self) -> Result<()> {
+		if self.is_cancelled() {
+			Err(Cancelled.into())
+		} else {
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn timeouts_cancel_the_current_token() {
+		let cancelled = CancellationToken::with_timeout(Some(Duration::from_millis(10)), || {
+			let token = CancellationToken::current();
+			while token.check().is_ok() {
+				thread::yield_now();
+			}
+			token.check()
+		});
+		assert!(cancelled.unwrap_err().is::<Cancelled>());
+
+		let finished = CancellationToken::with_timeout(Some(Duration::from_secs(60)), || {
+			CancellationToken::current().check()
+		});
+		assert!(finished.is_ok());
+		assert!(!CancellationToken::current().is_cancelled());
+	}
+}
@@ -0,0 +1,217 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Whatever performs the actual requests, so tests don't have to talk to the real site.
+pub trait Http {
+	/// Gets `url` with the session cookie set and returns the body of a successful response.
+	fn get(&self, url: &str, session: &str) -> Result<String>;
+}
+
+pub struct Ureq {
+	agent: ureq::Agent,
+}
+
+impl Default for Ureq {
+	fn default() -> Self {
+		Self {
+			agent: ureq::AgentBuilder::new()
+				.timeout(Duration::from_secs(30))
+				.user_agent(concat!(
+					env!("CARGO_PKG_NAME"),
+					"/",
+					env!("CARGO_PKG_VERSION")
+				))
+				.build(),
+		}
+	}
+}
+
+impl Http for Ureq {
+	fn get(&self, url: &str, session: &str) -> Result<String> {
+		let response = self
+			.agent
+			.get(url)
+			.set("Cookie", &format!("session={session}"))
+			.call()
+			.map_err(|error| match error {
+				ureq::Error::Status(status, response) => {
+					eyre!("{url} returned {status} {}", response.status_text())
+				}
+				error => eyre!(error).wrap_err(format!("failed to get {url}")),
+			})?;
+		response
+			.into_string()
+			.wrap_err_with(|| format!("failed to read the response from {url}"))
+	}
+}
+
+/// Downloads inputs into `inputs/YYYY/day-XX`, unless they are already there.
+pub struct Fetcher<H> {
+	http: H,
+	base_url: String,
+	session: String,
+	inputs: PathBuf,
+}
+
+impl Fetcher<Ureq> {
+	/// Reads the session token from `AOC_SESSION` and the base URL from `AOC_BASE_URL`, which
+	/// defaults to the Advent of Code site.
+	pub fn from_env() -> Result<Self> {
+		let session = std::env::var("AOC_SESSION")
+			.wrap_err("set AOC_SESSION to the session cookie to fetch inputs")?;
+		let base_url =
+			std::env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+		Ok(Self::new(Ureq::default(), &base_url, &session, "inputs"))
+	}
+}
+
+impl<H: Http> Fetcher<H> {
+	pub fn new(http: H, base_url: &str, session: &str, inputs: impl Into<PathBuf>) -> Self {
+		Self {
+			http,
+			base_url: base_url.trim_end_matches('/').to_string(),
+			session: session.trim().to_string(),
+			inputs: inputs.into(),
+		}
+	}
+
+	pub fn input_path(&self, year: usize, day: usize) -> PathBuf {
+		self.inputs.join(format!("{year}/day-{day:02}"))
+	}
+
+	/// Returns the path of the input, downloading it first if it isn't cached yet.
+	pub fn fetch(&self, year: usize, day: usize) -> Result<PathBuf> {
+		let path = self.input_path(year, day);
+		if path.exists() {
+			return Ok(path);
+		}
+
+		let url = format!("{}/{year}/day/{day}/input", self.base_url);
+		let input = self.http.get(&url, &self.session)?;
+		write_atomically(&path, &input)?;
+		Ok(path)
+	}
+}
+
+// an interrupted download must not end up looking like a cached input
+fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+	if let Some(directory) = path.parent() {
+		fs::create_dir_all(directory)
+			.wrap_err_with(|| format!("failed to create {}", directory.display()))?;
+	}
+	let partial = path.with_extension("part");
+	fs::write(&partial, contents)
+		.wrap_err_with(|| format!("failed to write {}", partial.display()))?;
+	fs::rename(&partial, path).wrap_err_with(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::cell::RefCell;
+	use std::io::{BufRead, BufReader, Write};
+	use std::net::TcpListener;
+	use std::thread;
+
+	#[derive(Default)]
+	struct Recording {
+		requests: RefCell<Vec<(String, String)>>,
+	}
+
+	impl Http for &Recording {
+		fn get(&self, url: &str, session: &str) -> Result<String> {
+			self.requests
+				.borrow_mut()
+				.push((url.to_string(), session.to_string()));
+			Ok("1000\n2000\n".to_string())
+		}
+	}
+
+	#[test]
+	fn inputs_are_downloaded_once() -> Result<()> {
+		let inputs = tempfile::tempdir()?;
+		let http = Recording::default();
+		let fetcher = Fetcher::new(&http, "https://example.com/", "secret\n", inputs.path());
+
+		for _ in 0..2 {
+			let path = fetcher.fetch(2022, 1)?;
+			assert_eq!(path, inputs.path().join("2022/day-01"));
+			assert_eq!(fs::read_to_string(path)?, "1000\n2000\n");
+		}
+		assert_eq!(
+			*http.requests.borrow(),
+			[(
+				"https://example.com/2022/day/1/input".to_string(),
+				"secret".to_string()
+			)]
+		);
+		Ok(())
+	}
+
+	// answers each connection with the next of `responses` and returns the base URL and the
+	// request lines it received
+	fn serve(
+		responses: &'static [(&'static str, &'static str)],
+	) -> Result<(String, thread::JoinHandle<Vec<String>>)> {
+		let listener = TcpListener::bind("127.0.0.1:0")?;
+		let base_url = format!("http://{}", listener.local_addr()?);
+		let server = thread::spawn(move || {
+			let mut received = Vec::new();
+			for (status, body) in responses {
+				let (mut stream, _) = listener.accept().unwrap();
+				let mut reader = BufReader::new(stream.try_clone().unwrap());
+				let mut line = String::new();
+				while reader.read_line(&mut line).unwrap() > 2 {
+					received.push(line.trim_end().to_string());
+					line.clear();
+				}
+				write!(
+					stream,
+					"HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+					body.len()
+				)
+				.unwrap();
+			}
+			received
+		});
+		Ok((base_url, server))
+	}
+
+	#[test]
+	fn ureq_sends_the_session_cookie() -> Result<()> {
+		let (base_url, server) = serve(&[("200 OK", "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n")])?;
+		let inputs = tempfile::tempdir()?;
+		let fetcher = Fetcher::new(Ureq::default(), &base_url, "secret", inputs.path());
+
+		let path = fetcher.fetch(2022, 6)?;
+		assert_eq!(
+			fs::read_to_string(path)?,
+			"mjqjpqmgbljsphdztnvjfqwrcgsmlb\n"
+		);
+		let received = server.join().unwrap();
+		assert_eq!(received[0], "GET /2022/day/6/input HTTP/1.1");
+		assert!(received.contains(&"Cookie: session=secret".to_string()));
+		Ok(())
+	}
+
+	#[test]
+	fn failed_downloads_are_not_cached() -> Result<()> {
+		let (base_url, server) = serve(&[(
+			"404 Not Found",
+			"Please don't repeatedly request this endpoint before it unlocks!",
+		)])?;
+		let inputs = tempfile::tempdir()?;
+		let fetcher = Fetcher::new(Ureq::default(), &base_url, "secret", inputs.path());
+
+		assert!(fetcher.fetch(2022, 25).is_err());
+		assert!(!fetcher.input_path(2022, 25).exists());
+		server.join().unwrap();
+		Ok(())
+	}
+}
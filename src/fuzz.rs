@@ -0,0 +1,72 @@
+use crate::find_solution;
+
+/// Feeds arbitrary bytes to the parser of a day, like the fuzz targets in `fuzz/` do. Errors are
+/// fine, panics are not.
+pub fn parse(year: usize, day: usize, data: &[u8]) {
+	let solution = find_solution(year, day).expect("only registered days are fuzzed");
+	let _ = (solution.parse)(&String::from_utf8_lossy(data));
+}
+
+#[cfg(test)]
+mod tests {
+	use std::panic::{catch_unwind, AssertUnwindSafe};
+	use std::path::Path;
+
+	use color_eyre::eyre::{eyre, Result};
+
+	use super::*;
+	use crate::solutions;
+
+	/// Small edits of `data` that tend to trip up parsers: every prefix, every line left out and every
+	/// byte replaced by something a puzzle input might contain in another place.
+	fn mutations(data: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+		let prefixes = (0..data.len()).map(|end| data[..end].to_vec());
+		let lines: Vec<_> = data.split_inclusive(|&byte| byte == b'\n').collect();
+		let without_lines = (0..lines.len()).map(move |skip| {
+			let mut lines = lines.clone();
+			lines.remove(skip);
+			lines.concat()
+		});
+		let replaced = (0..data.len()).flat_map(move |index| {
+			b"\n 09-#.".iter().map(move |&byte| {
+				let mut data = data.to_vec();
+				data[index] = byte;
+				data
+			})
+		});
+		prefixes.chain(without_lines).chain(replaced)
+	}
+
+	/// Runs every parser on the mutations of its fuzzing corpus in `corpus` (one `day_XX` directory of
+	/// inputs per day), returning the days and inputs that made it panic.
+	fn check_corpus(corpus: &Path) -> Result<Vec<(usize, Vec<u8>)>> {
+		let mut panics = Vec::new();
+		for solution in solutions()? {
+			let directory = corpus.join(format!("day_{:02}", solution.day));
+			for entry in directory
+				.read_dir()
+				.map_err(|error| eyre!("{}: {error}", directory.display()))?
+			{
+				let data = std::fs::read(entry?.path())?;
+				for input in mutations(&data) {
+					let parse = || parse(solution.year, solution.day, &input);
+					if catch_unwind(AssertUnwindSafe(parse)).is_err() {
+						panics.push((solution.day, input));
+					}
+				}
+			}
+		}
+		Ok(panics)
+	}
+
+	#[test]
+	fn parsers_never_panic_on_the_corpus() -> Result<()> {
+		let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus");
+		let panics: Vec<_> = check_corpus(&corpus)?
+			.into_iter()
+			.map(|(day, input)| format!("day {day}: {:?}", String::from_utf8_lossy(&input)))
+			.collect();
+		assert!(panics.is_empty(), "{}", panics.join("\n"));
+		Ok(())
+	}
+}
@@ -0,0 +1,63 @@
+use color_eyre::eyre::Result;
+use rand::SeedableRng;
+
+use crate::find_solution;
+
+/// The random number generator of the input generators. ChaCha is portable, so a seed gives the
+/// same input on every machine.
+pub type InputRng = rand_chacha::ChaCha8Rng;
+
+/// Generates an input for a day from `seed`, see [`Puzzle::generate`](crate::Puzzle::generate).
+pub fn generate(year: usize, day: usize, seed: u64, size: usize) -> Result<String> {
+	let solution = find_solution(year, day)?;
+	(solution.generate)(&mut InputRng::seed_from_u64(seed), size)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use color_eyre::eyre::WrapErr;
+
+	use super::*;
+	use crate::runner::{run, TimedOut};
+	use crate::solutions;
+
+	#[test]
+	fn generated_inputs_pass_the_checks_and_can_be_solved() -> Result<()> {
+		// the slow searches give up after a while, but every part that finishes has to succeed
+		let timeout = Some(Duration::from_secs(1));
+		for solution in solutions()? {
+			for (seed, size) in [(1, 1), (2, 5), (3, 20)] {
+				let context = || format!("{} with seed {seed} and size {size}", solution.name());
+				let input =
+					generate(solution.year, solution.day, seed, size).wrap_err_with(context)?;
+				(solution.parse)(&input).wrap_err_with(context)?;
+				for check in (solution.lint)(&input)? {
+					check
+						.result
+						.wrap_err_with(|| format!("expected that {}", check.name))
+						.wrap_err_with(context)?;
+				}
+				for part in run(solution, &input, None, timeout).parts {
+					match part.answer {
+						Err(report) if report.is::<TimedOut>() => {}
+						answer => {
+							answer
+								.wrap_err_with(|| format!("part {} failed", part.part))
+								.wrap_err_with(context)?;
+						}
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn seeds_are_reproducible() -> Result<()> {
+		assert_eq!(generate(2022, 16, 7, 30)?, generate(2022, 16, 7, 30)?);
+		assert_ne!(generate(2022, 16, 7, 30)?, generate(2022, 16, 8, 30)?);
+		Ok(())
+	}
+}
@@ -0,0 +1,356 @@
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A position (or an offset between two) with `N` coordinates. In 2D these are `[row, column]`,
+/// so that they line up with the indices of a grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> Default for Point<N> {
+	fn default() -> Self {
+		Self::ORIGIN
+	}
+}
+
+impl<const N: usize> Point<N> {
+	pub const ORIGIN: Self = Point([0; N]);
+
+	/// The offset of a single step along `axis`, forwards or backwards.
+	pub fn unit(axis: usize, negative: bool) -> Self {
+		let mut unit = Self::ORIGIN;
+		unit[axis] = if negative { -1 } else { 1 };
+		unit
+	}
+
+	pub fn map(self, function: impl FnMut(i64) -> i64) -> Self {
+		Point(self.0.map(function))
+	}
+
+	/// The sign of every coordinate, i.e. the single step that gets closest to `self`.
+	pub fn signum(self) -> Self {
+		self.map(i64::signum)
+	}
+
+	/// Wraps every coordinate around to `0..size`, like on a torus.
+	pub fn rem_euclid(self, size: Self) -> Self {
+		Point(std::array::from_fn(|axis| {
+			self[axis].rem_euclid(size[axis])
+		}))
+	}
+
+	/// The distance when moving along the axes only.
+	pub fn manhattan(self, other: Self) -> i64 {
+		(self - other).0.iter().map(|d| d.abs()).sum()
+	}
+
+	/// The distance when diagonal moves count as one step, like a king's on a chess board.
+	pub fn chebyshev(self, other: Self) -> i64 {
+		(self - other).0.iter().map(|d| d.abs()).max().unwrap_or(0)
+	}
+
+	/// The 2N points one step away along the axes.
+	pub fn neighbors(self) -> impl Iterator<Item = Self> {
+		(0..N).flat_map(move |axis| [true, false].map(|negative| self + Self::unit(axis, negative)))
+	}
+
+	pub fn from_index(index: [usize; N]) -> Self {
+		Point(index.map(|i| i as i64))
+	}
+
+	/// The index of this point in an array of the given `shape`, unless it lies outside of it.
+	pub fn to_index(self, shape: [usize; N]) -> Option<[usize; N]> {
+		let mut index = [0; N];
+		for axis in 0..N {
+			index[axis] = usize::try_from(self[axis])
+				.ok()
+				.filter(|&i| i < shape[axis])?;
+		}
+		Some(index)
+	}
+}
+
+impl Point<2> {
+	/// Rotates an offset by a quarter turn, clockwise as seen with rows going down.
+	pub fn rotate_right(self) -> Self {
+		let [row, column] = self.0;
+		Point([column, -row])
+	}
+
+	pub fn rotate_left(self) -> Self {
+		let [row, column] = self.0;
+		Point([-column, row])
+	}
+}
+
+impl<const N: usize> From<[i64; N]> for Point<N> {
+	fn from(coordinates: [i64; N]) -> Self {
+		Point(coordinates)
+	}
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+	type Output = i64;
+
+	fn index(&self, axis: usize) -> &i64 {
+		&self.0[axis]
+	}
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+	fn index_mut(&mut self, axis: usize) -> &mut i64 {
+		&mut self.0[axis]
+	}
+}
+
+impl<const N: usize> Add for Point<N> {
+	type Output = Self;
+
+	fn add(mut self, other: Self) -> Self {
+		self += other;
+		self
+	}
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+	fn add_assign(&mut self, other: Self) {
+		self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a += b);
+	}
+}
+
+impl<const N: usize> Sub for Point<N> {
+	type Output = Self;
+
+	fn sub(mut self, other: Self) -> Self {
+		self -= other;
+		self
+	}
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+	fn sub_assign(&mut self, other: Self) {
+		self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a -= b);
+	}
+}
+
+impl<const N: usize> Neg for Point<N> {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		self.map(|a| -a)
+	}
+}
+
+impl<const N: usize> Mul<i64> for Point<N> {
+	type Output = Self;
+
+	fn mul(self, factor: i64) -> Self {
+		self.map(|a| a * factor)
+	}
+}
+
+/// The four directions along the axes of a grid, in clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+	Up,
+	Right,
+	Down,
+	Left,
+}
+
+impl Dir4 {
+	pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+	pub fn offset(self) -> Point<2> {
+		match self {
+			Dir4::Up => Point([-1, 0]),
+			Dir4::Right => Point([0, 1]),
+			Dir4::Down => Point([1, 0]),
+			Dir4::Left => Point([0, -1]),
+		}
+	}
+
+	pub fn from_offset(offset: Point<2>) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|direction| direction.offset() == offset)
+	}
+
+	pub fn turn_right(self) -> Self {
+		Self::ALL[(self as usize + 1) % 4]
+	}
+
+	pub fn turn_left(self) -> Self {
+		Self::ALL[(self as usize + 3) % 4]
+	}
+
+	pub fn reverse(self) -> Self {
+		Self::ALL[(self as usize + 2) % 4]
+	}
+}
+
+/// The eight directions including the diagonals, in clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+	N,
+	NE,
+	E,
+	SE,
+	S,
+	SW,
+	W,
+	NW,
+}
+
+impl Dir8 {
+	pub const ALL: [Dir8; 8] = [
+		Dir8::N,
+		Dir8::NE,
+		Dir8::E,
+		Dir8::SE,
+		Dir8::S,
+		Dir8::SW,
+		Dir8::W,
+		Dir8::NW,
+	];
+
+	pub fn offset(self) -> Point<2> {
+		match self {
+			Dir8::N => Point([-1, 0]),
+			Dir8::NE => Point([-1, 1]),
+			Dir8::E => Point([0, 1]),
+			Dir8::SE => Point([1, 1]),
+			Dir8::S => Point([1, 0]),
+			Dir8::SW => Point([1, -1]),
+			Dir8::W => Point([0, -1]),
+			Dir8::NW => Point([-1, -1]),
+		}
+	}
+
+	/// Turns by an eighth of a turn.
+	pub fn turn_right(self) -> Self {
+		Self::ALL[(self as usize + 1) % 8]
+	}
+
+	pub fn turn_left(self) -> Self {
+		Self::ALL[(self as usize + 7) % 8]
+	}
+}
+
+impl From<Dir4> for Dir8 {
+	fn from(direction: Dir4) -> Self {
+		Self::ALL[2 * direction as usize]
+	}
+}
+
+/// The smallest box containing a set of points, including its edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds<const N: usize> {
+	pub min: Point<N>,
+	pub max: Point<N>,
+}
+
+impl<const N: usize> Bounds<N> {
+	/// The bounds of just `point`.
+	pub fn around(point: Point<N>) -> Self {
+		Bounds {
+			min: point,
+			max: point,
+		}
+	}
+
+	/// The bounds of all `points`, or `None` if there are none.
+	pub fn from_points(points: impl IntoIterator<Item = Point<N>>) -> Option<Self> {
+		let mut points = points.into_iter();
+		let mut bounds = Self::around(points.next()?);
+		points.for_each(|point| bounds.extend(point));
+		Some(bounds)
+	}
+
+	pub fn extend(&mut self, point: Point<N>) {
+		for axis in 0..N {
+			self.min[axis] = self.min[axis].min(point[axis]);
+			self.max[axis] = self.max[axis].max(point[axis]);
+		}
+	}
+
+	pub fn contains(&self, point: Point<N>) -> bool {
+		(0..N).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+	}
+
+	/// The number of points along each axis.
+	pub fn size(&self) -> [usize; N] {
+		std::array::from_fn(|axis| (self.max[axis] - self.min[axis] + 1) as usize)
+	}
+
+	/// The number of points within the bounds.
+	pub fn volume(&self) -> usize {
+		self.size().iter().product()
+	}
+
+	/// The index of `point` in an array of [`size`](Bounds::size) that starts at `min`.
+	pub fn index(&self, point: Point<N>) -> Option<[usize; N]> {
+		(point - self.min).to_index(self.size())
+	}
+}
+
+/// The indices one step away from `index` along the axes that are still within `shape`, like
+/// the neighbours of a tile in a grid.
+pub fn grid_neighbors<const N: usize>(
+	index: [usize; N],
+	shape: [usize; N],
+) -> impl Iterator<Item = [usize; N]> {
+	(0..N).flat_map(move |axis| {
+		let before = index[axis].checked_sub(1);
+		let after = Some(index[axis] + 1).filter(|&i| i < shape[axis]);
+		[before, after].into_iter().flatten().map(move |i| {
+			let mut neighbor = index;
+			neighbor[axis] = i;
+			neighbor
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn turns_match_rotations() {
+		for direction in Dir4::ALL {
+			let offset = direction.offset();
+			assert_eq!(direction.turn_right().offset(), offset.rotate_right());
+			assert_eq!(direction.turn_left().offset(), offset.rotate_left());
+			assert_eq!(direction.reverse().offset(), -offset);
+			assert_eq!(Dir8::from(direction).offset(), offset);
+			assert_eq!(Dir4::from_offset(offset), Some(direction));
+		}
+		assert_eq!(Dir8::N.turn_left(), Dir8::NW);
+		assert_eq!(Dir8::NW.turn_right(), Dir8::N);
+	}
+
+	#[test]
+	fn metrics() {
+		let (a, b) = (Point([1, -2]), Point([-3, 4]));
+		assert_eq!(a.manhattan(b), 10);
+		assert_eq!(a.chebyshev(b), 6);
+		assert_eq!((b - a).signum(), Point([-1, 1]));
+	}
+
+	#[test]
+	fn bounds_index_their_points() {
+		let bounds = Bounds::from_points([Point([3, -1, 0]), Point([1, 2, 0])]).unwrap();
+		assert_eq!(bounds.size(), [3, 4, 1]);
+		assert_eq!(bounds.volume(), 12);
+		assert!(bounds.contains(Point([2, 0, 0])));
+		assert!(!bounds.contains(Point([0, 0, 0])));
+		assert_eq!(bounds.index(Point([2, 0, 0])), Some([1, 1, 0]));
+		assert_eq!(bounds.index(Point([2, 3, 0])), None);
+	}
+
+	#[test]
+	fn grid_neighbors_stay_within_the_grid() {
+		let corner: Vec<_> = grid_neighbors([0, 0], [2, 3]).collect();
+		assert_eq!(corner, [[1, 0], [0, 1]]);
+		assert_eq!(grid_neighbors([1, 1], [3, 3]).count(), 4);
+		assert_eq!(Point([0, 0, 0]).neighbors().count(), 6);
+	}
+}
@@ -0,0 +1,252 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Deref, DerefMut};
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::prelude::*;
+
+/// Tiles that are drawn as a single byte in the puzzle input, like `#` for a wall.
+pub trait FromGridByte: Sized {
+	/// The tile drawn as `byte`, or `None` if it isn't one.
+	fn from_grid_byte(byte: u8) -> Option<Self>;
+}
+
+/// The raw bytes, for maps that need a closer look (e.g. at markers) before they are converted.
+impl FromGridByte for u8 {
+	fn from_grid_byte(byte: u8) -> Option<Self> {
+		Some(byte)
+	}
+}
+
+/// Why a character map couldn't be read. Rows and columns count from zero, but are shown as the
+/// line and column of the input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+	/// A line that is shorter or longer than the first one.
+	Width {
+		row: usize,
+		width: usize,
+		expected: usize,
+	},
+	/// A byte that isn't a tile.
+	Tile { row: usize, column: usize, byte: u8 },
+}
+
+impl Display for GridError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match *self {
+			GridError::Width {
+				row,
+				width,
+				expected,
+			} => write!(f, "line {} is {width} wide instead of {expected}", row + 1),
+			GridError::Tile { row, column, byte } => write!(
+				f,
+				"unexpected {:?} at line {}, column {}",
+				byte as char,
+				row + 1,
+				column + 1
+			),
+		}
+	}
+}
+
+impl Error for GridError {}
+
+/// A 2D map read from the puzzle input, indexed by `[row, column]` and otherwise used like the
+/// [`Array2`] it wraps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T>(Array2<T>);
+
+impl<T: FromGridByte> Grid<T> {
+	/// Reads a map whose lines all have the same width.
+	pub fn parse(input: &str) -> Result<Self> {
+		Self::parse_with(input, T::from_grid_byte)
+	}
+}
+
+impl<T: FromGridByte + Clone> Grid<T> {
+	/// Reads a map whose lines may be shorter than the widest one, filling them up with `padding`.
+	pub fn parse_padded(input: &str, padding: T) -> Result<Self> {
+		let lines = read_lines(input, T::from_grid_byte)?;
+		let width = lines
+			.iter()
+			.map(|(_, tiles)| tiles.len())
+			.max()
+			.unwrap_or(0);
+		let values = lines
+			.into_iter()
+			.flat_map(|(_, mut tiles)| {
+				tiles.resize(width, padding.clone());
+				tiles
+			})
+			.collect::<Vec<_>>();
+		Ok(Grid(Array2::from_shape_vec(
+			(values.len() / width.max(1), width),
+			values,
+		)?))
+	}
+}
+
+impl<T> Grid<T> {
+	/// Like [`Grid::parse`], with `convert` telling the tiles apart instead of [`FromGridByte`].
+	pub fn parse_with(input: &str, convert: impl FnMut(u8) -> Option<T>) -> Result<Self> {
+		let lines = read_lines(input, convert)?;
+		let expected = lines.first().map_or(0, |(_, tiles)| tiles.len());
+		if let Some((row, tiles)) = lines.iter().find(|(_, tiles)| tiles.len() != expected) {
+			return Err(GridError::Width {
+				row: *row,
+				width: tiles.len(),
+				expected,
+			}
+			.into());
+		}
+		let rows = lines.len();
+		let values = lines.into_iter().flat_map(|(_, tiles)| tiles).collect();
+		Ok(Grid(Array2::from_shape_vec((rows, expected), values)?))
+	}
+
+	pub fn into_inner(self) -> Array2<T> {
+		self.0
+	}
+}
+
+impl Grid<u8> {
+	/// Finds the only `marker` in the map, like the start of a path, and replaces it with the
+	/// tile that is under it.
+	pub fn take_marker(&mut self, marker: u8, replacement: u8) -> Result<[usize; 2]> {
+		let mut positions = self
+			.indexed_iter()
+			.filter(|(_, &byte)| byte == marker)
+			.map(|((row, column), _)| [row, column]);
+		let position = positions
+			.next()
+			.ok_or_else(|| eyre!("no {:?} in the map", marker as char))?;
+		if let Some([row, column]) = positions.next() {
+			return Err(eyre!(
+				"another {:?} at line {}, column {}",
+				marker as char,
+				row + 1,
+				column + 1
+			));
+		}
+		self[position] = replacement;
+		Ok(position)
+	}
+
+	/// Converts every byte, failing on the first one that `convert` doesn't accept.
+	pub fn try_map<U>(&self, mut convert: impl FnMut(u8) -> Option<U>) -> Result<Grid<U>> {
+		let values = self
+			.indexed_iter()
+			.map(|((row, column), &byte)| {
+				convert(byte).ok_or(GridError::Tile { row, column, byte })
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Grid(Array2::from_shape_vec(self.raw_dim(), values)?))
+	}
+}
+
+impl<T> Deref for Grid<T> {
+	type Target = Array2<T>;
+
+	fn deref(&self) -> &Array2<T> {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Grid<T> {
+	fn deref_mut(&mut self) -> &mut Array2<T> {
+		&mut self.0
+	}
+}
+
+impl<T> From<Grid<T>> for Array2<T> {
+	fn from(grid: Grid<T>) -> Self {
+		grid.0
+	}
+}
+
+/// The tiles of every non-blank line along with its row. Blank lines are skipped, but still count
+/// towards the rows, so that errors point at the right line.
+fn read_lines<T>(
+	input: &str,
+	mut convert: impl FnMut(u8) -> Option<T>,
+) -> Result<Vec<(usize, Vec<T>)>, GridError> {
+	input
+		.lines()
+		.enumerate()
+		.filter(|(_, line)| !line.trim().is_empty())
+		.map(|(row, line)| {
+			let tiles = line
+				.bytes()
+				.enumerate()
+				.map(|(column, byte)| convert(byte).ok_or(GridError::Tile { row, column, byte }))
+				.collect::<Result<_, _>>()?;
+			Ok((row, tiles))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+	enum Tile {
+		Open,
+		Wall,
+		Void,
+	}
+
+	impl FromGridByte for Tile {
+		fn from_grid_byte(byte: u8) -> Option<Self> {
+			match byte {
+				b'.' => Some(Tile::Open),
+				b'#' => Some(Tile::Wall),
+				b' ' => Some(Tile::Void),
+				_ => None,
+			}
+		}
+	}
+
+	#[test]
+	fn errors_point_at_the_line() {
+		let error = Grid::<Tile>::parse("..#\n\n.#\n").unwrap_err();
+		assert_eq!(
+			error.downcast_ref::<GridError>(),
+			Some(&GridError::Width {
+				row: 2,
+				width: 2,
+				expected: 3
+			})
+		);
+		assert_eq!(error.to_string(), "line 3 is 2 wide instead of 3");
+
+		let error = Grid::<Tile>::parse("..#\n.x.\n").unwrap_err();
+		assert_eq!(error.to_string(), "unexpected 'x' at line 2, column 2");
+	}
+
+	#[test]
+	fn ragged_lines_are_padded() -> Result<()> {
+		let grid = Grid::parse_padded("  .#\n.\n", Tile::Void)?;
+		assert_eq!(
+			grid.into_inner(),
+			array![
+				[Tile::Void, Tile::Void, Tile::Open, Tile::Wall],
+				[Tile::Open, Tile::Void, Tile::Void, Tile::Void],
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn markers_are_taken_out() -> Result<()> {
+		let mut grid = Grid::<u8>::parse("Sab\ncdE\n")?;
+		assert_eq!(grid.take_marker(b'S', b'a')?, [0, 0]);
+		assert_eq!(grid.take_marker(b'E', b'z')?, [1, 2]);
+		assert!(grid.take_marker(b'S', b'a').is_err());
+		let heights = grid.try_map(|byte| byte.checked_sub(b'a'))?;
+		assert_eq!(heights.into_inner(), array![[0, 0, 1], [2, 3, 25]]);
+		Ok(())
+	}
+}
@@ -0,0 +1,599 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use itertools::Itertools;
+use linkme::distributed_slice;
+use nom::combinator::all_consuming;
+use nom::error::{VerboseError, VerboseErrorKind};
+use nom::{Finish, IResult};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::generate::InputRng;
+use crate::lint::Check;
+use crate::sections::Sections;
+
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod cancel;
+pub mod fetch;
+pub mod fuzz;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod lint;
+pub mod runner;
+pub mod scaffold;
+pub mod sections;
+pub mod watch;
+
+mod solutions {
+	pub(crate) const YEAR: usize = 2022;
+
+	automod::dir!("src/solutions");
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+	Integer(i128),
+	Text(String),
+}
+
+impl Display for Answer {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Answer::Integer(integer) => integer.fmt(f),
+			Answer::Text(text) => f.write_str(text),
+		}
+	}
+}
+
+// integers are written as plain numbers where the format allows it, since not every format
+// (TOML in particular) supports 128-bit integers
+impl Serialize for Answer {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Answer::Integer(integer) => match i64::try_from(*integer) {
+				Ok(integer) => serializer.serialize_i64(integer),
+				Err(_) => serializer.serialize_i128(*integer),
+			},
+			Answer::Text(text) => serializer.serialize_str(text),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Answer {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct AnswerVisitor;
+
+		impl de::Visitor<'_> for AnswerVisitor {
+			type Value = Answer;
+
+			fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				f.write_str("an integer or a string")
+			}
+
+			fn visit_i64<E: de::Error>(self, integer: i64) -> Result<Answer, E> {
+				Ok(integer.into())
+			}
+
+			fn visit_u64<E: de::Error>(self, integer: u64) -> Result<Answer, E> {
+				Ok(integer.into())
+			}
+
+			fn visit_i128<E: de::Error>(self, integer: i128) -> Result<Answer, E> {
+				Ok(Answer::Integer(integer))
+			}
+
+			fn visit_str<E: de::Error>(self, text: &str) -> Result<Answer, E> {
+				Ok(text.into())
+			}
+		}
+
+		deserializer.deserialize_any(AnswerVisitor)
+	}
+}
+
+macro_rules! impl_answer_from_integer {
+	($($t:ty),*) => {
+		$(
+			impl From<$t> for Answer {
+				fn from(integer: $t) -> Self {
+					Answer::Integer(integer.into())
+				}
+			}
+		)*
+	};
+}
+
+impl_answer_from_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl From<usize> for Answer {
+	fn from(integer: usize) -> Self {
+		Answer::Integer(integer as i128)
+	}
+}
+
+impl From<isize> for Answer {
+	fn from(integer: isize) -> Self {
+		Answer::Integer(integer as i128)
+	}
+}
+
+impl From<String> for Answer {
+	fn from(text: String) -> Self {
+		Answer::Text(text)
+	}
+}
+
+impl From<&str> for Answer {
+	fn from(text: &str) -> Self {
+		Answer::Text(text.to_string())
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+	pub part1: Answer,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub part2: Option<Answer>,
+}
+
+impl Answers {
+	pub fn new(part1: impl Into<Answer>, part2: impl Into<Answer>) -> Self {
+		Self {
+			part1: part1.into(),
+			part2: Some(part2.into()),
+		}
+	}
+
+	pub fn part1_only(part1: impl Into<Answer>) -> Self {
+		Self {
+			part1: part1.into(),
+			part2: None,
+		}
+	}
+}
+
+pub trait Puzzle {
+	type Parsed: Send + Sync + 'static;
+
+	const HAS_PART_2: bool = true;
+
+	fn parse(input: &str) -> Result<Self::Parsed>;
+
+	fn part_1(parsed: &Self::Parsed) -> Result<Answer>;
+
+	fn part_2(_parsed: &Self::Parsed) -> Result<Answer> {
+		Err(eyre!("there is no second part"))
+	}
+
+	/// Checks the assumptions the parts make about the input, each on its own so that all of the
+	/// broken ones can be reported. Only fails if the input can't be parsed at all.
+	fn lint(_input: &str) -> Result<Vec<Check>> {
+		Ok(Vec::new())
+	}
+
+	/// Generates an input in the format of the puzzle that fits the assumptions the parts make.
+	/// `size` is roughly how many items (lines, monkeys, blueprints...) or how wide a map it has.
+	fn generate(_rng: &mut InputRng, _size: usize) -> Result<String> {
+		Err(eyre!("there is no input generator"))
+	}
+
+	fn solve(input: &str) -> Result<Answers> {
+		let parsed = Self::parse(input)?;
+		Ok(Answers {
+			part1: Self::part_1(&parsed)?,
+			part2: Self::HAS_PART_2
+				.then(|| Self::part_2(&parsed))
+				.transpose()?,
+		})
+	}
+}
+
+pub type Parsed = Box<dyn Any + Send + Sync>;
+
+pub struct Solution {
+	pub year: usize,
+	pub day: usize,
+	pub title: &'static str,
+	pub parse: fn(&str) -> Result<Parsed>,
+	pub part1: fn(&Parsed) -> Result<Answer>,
+	pub part2: Option<fn(&Parsed) -> Result<Answer>>,
+	pub lint: fn(&str) -> Result<Vec<Check>>,
+	pub generate: fn(&mut InputRng, usize) -> Result<String>,
+}
+
+impl Solution {
+	pub const fn new<P: Puzzle>(year: usize, day: usize, title: &'static str) -> Self {
+		fn parse<P: Puzzle>(input: &str) -> Result<Parsed> {
+			Ok(Box::new(P::parse(input)?))
+		}
+
+		fn downcast<P: Puzzle>(parsed: &Parsed) -> Result<&P::Parsed> {
+			parsed
+				.downcast_ref()
+				.ok_or_else(|| eyre!("input was parsed by a different solution"))
+		}
+
+		fn part1<P: Puzzle>(parsed: &Parsed) -> Result<Answer> {
+			P::part_1(downcast::<P>(parsed)?)
+		}
+
+		fn part2<P: Puzzle>(parsed: &Parsed) -> Result<Answer> {
+			P::part_2(downcast::<P>(parsed)?)
+		}
+
+		Self {
+			year,
+			day,
+			title,
+			parse: parse::<P>,
+			part1: part1::<P>,
+			part2: if P::HAS_PART_2 {
+				Some(part2::<P>)
+			} else {
+				None
+			},
+			lint: P::lint,
+			generate: P::generate,
+		}
+	}
+
+	pub fn solve(&self, input: &str) -> Result<Answers> {
+		let parsed = (self.parse)(input)?;
+		Ok(Answers {
+			part1: (self.part1)(&parsed)?,
+			part2: self.part2.map(|part2| part2(&parsed)).transpose()?,
+		})
+	}
+
+	/// The puzzle this solves, e.g. `2022/17`.
+	pub fn name(&self) -> String {
+		format!("{}/{}", self.year, self.day)
+	}
+
+	/// The parts of this day, numbered from 1.
+	pub fn parts(&self) -> impl Iterator<Item = (usize, fn(&Parsed) -> Result<Answer>)> {
+		(1..).zip(Some(self.part1).into_iter().chain(self.part2))
+	}
+
+	pub fn part(&self, number: usize) -> Result<fn(&Parsed) -> Result<Answer>> {
+		self.parts()
+			.find(|(n, _)| *n == number)
+			.map(|(_, part)| part)
+			.ok_or_else(|| eyre!("{} has no part {number}", self.name()))
+	}
+}
+
+#[distributed_slice]
+pub static SOLUTIONS: [Solution] = [..];
+
+/// Parses the day out of a module path ending in `day_XX`, like the ones in `src/solutions`.
+pub const fn day_from_module_path(path: &str) -> Option<usize> {
+	let path = path.as_bytes();
+	let mut start = path.len();
+	while start > 0 && path[start - 1] != b':' {
+		start -= 1;
+	}
+	let prefix = b"day_";
+	if path.len() - start <= prefix.len() {
+		return None;
+	}
+	let mut i = 0;
+	while i < prefix.len() {
+		if path[start + i] != prefix[i] {
+			return None;
+		}
+		i += 1;
+	}
+	let mut day = 0;
+	let mut i = start + prefix.len();
+	while i < path.len() {
+		if !path[i].is_ascii_digit() {
+			return None;
+		}
+		day = 10 * day + (path[i] - b'0') as usize;
+		i += 1;
+	}
+	Some(day)
+}
+
+/// Registers a [`Puzzle`] for the year of the enclosing module (its parent's `YEAR` constant).
+/// The day has to match the one in the module name, so `day_17.rs` has to register day 17:
+///
+/// ```
+/// # use advent_of_code_2022::{solution, Answer, Puzzle};
+/// # use color_eyre::eyre::Result;
+/// const YEAR: usize = 2022;
+///
+/// mod day_17 {
+/// #   use super::*;
+///     struct Day17;
+/// #   impl Puzzle for Day17 {
+/// #       type Parsed = ();
+/// #       fn parse(_input: &str) -> Result<()> { Ok(()) }
+/// #       fn part_1(_parsed: &()) -> Result<Answer> { Ok(0.into()) }
+/// #   }
+///     solution!(Day17, day = 17, title = "Pyroclastic Flow");
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```compile_fail
+/// # use advent_of_code_2022::{solution, Answer, Puzzle};
+/// # use color_eyre::eyre::Result;
+/// const YEAR: usize = 2022;
+///
+/// mod day_17 {
+/// #   use super::*;
+///     struct Day18;
+/// #   impl Puzzle for Day18 {
+/// #       type Parsed = ();
+/// #       fn parse(_input: &str) -> Result<()> { Ok(()) }
+/// #       fn part_1(_parsed: &()) -> Result<Answer> { Ok(0.into()) }
+/// #   }
+///     solution!(Day18, day = 18, title = "Boiling Boulders");
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! solution {
+	($puzzle:ty, day = $day:literal, title = $title:literal $(,)?) => {
+		const _: () = assert!(
+			matches!($crate::day_from_module_path(module_path!()), Some($day)),
+			concat!(
+				"day ",
+				$day,
+				" has to be registered in its own day_XX module"
+			),
+		);
+
+		#[::linkme::distributed_slice($crate::SOLUTIONS)]
+		static SOLUTION: $crate::Solution =
+			$crate::Solution::new::<$puzzle>(super::YEAR, $day, $title);
+	};
+}
+
+/// All registered solutions ordered by year and day, or an error if a day was registered more
+/// than once.
+pub fn solutions() -> Result<Vec<&'static Solution>> {
+	let mut solutions: Vec<_> = SOLUTIONS.iter().collect();
+	solutions.sort_by_key(|solution| (solution.year, solution.day));
+	if let Some([solution, _]) = solutions
+		.array_windows()
+		.find(|[first, second]| (first.year, first.day) == (second.year, second.day))
+	{
+		return Err(eyre!("{} is registered more than once", solution.name()));
+	}
+	Ok(solutions)
+}
+
+pub fn find_solution(year: usize, day: usize) -> Result<&'static Solution> {
+	let solutions = solutions()?;
+	solutions
+		.iter()
+		.find(|solution| (solution.year, solution.day) == (year, day))
+		.copied()
+		.ok_or_else(|| {
+			eyre!(
+				"{year}/{day} is not implemented, available days are {}",
+				format_days(&solutions)
+			)
+		})
+}
+
+/// Formats sorted solutions with consecutive days collapsed, e.g. `2021/1-3, 2022/5`.
+pub fn format_days(solutions: &[&Solution]) -> String {
+	let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+	for solution in solutions {
+		match runs.last_mut() {
+			Some((year, _, last)) if *year == solution.year && *last + 1 == solution.day => {
+				*last = solution.day
+			}
+			_ => runs.push((solution.year, solution.day, solution.day)),
+		}
+	}
+	if runs.is_empty() {
+		return "none".to_string();
+	}
+	runs.into_iter()
+		.map(|(year, first, last)| {
+			if first == last {
+				format!("{year}/{first}")
+			} else {
+				format!("{year}/{first}-{last}")
+			}
+		})
+		.join(", ")
+}
+
+/// The line (counting from one) that an error happened on, added by [`try_for_each_line`].
+#[derive(Debug)]
+struct Line(usize);
+
+impl Display for Line {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "line {}", self.0)
+	}
+}
+
+/// Calls `function` with every line (including its line ending), adding the line number to any
+/// error it returns.
+fn try_for_each_line<F>(input: &str, mut function: F) -> Result<()>
+where
+	F: FnMut(&str) -> Result<()>,
+{
+	for (number, line) in input.split_inclusive('\n').enumerate() {
+		function(line).wrap_err(Line(number + 1))?;
+	}
+
+	Ok(())
+}
+
+/// The text of a capture group, or an error (instead of a panic) if it didn't match anything.
+fn capture<'t>(captures: &regex::Captures<'t>, group: usize) -> Result<&'t str> {
+	captures
+		.get(group)
+		.map(|capture| capture.as_str())
+		.ok_or_else(|| eyre!("nothing matched group {group}"))
+}
+
+/// The result of a nom parser, whose errors remember where in the input they happened.
+type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// Where a nom parser got stuck, see [`parse_all`].
+#[derive(Debug)]
+struct ParseError {
+	line: usize,
+	column: usize,
+	expected: String,
+	found: String,
+	text: String,
+}
+
+impl ParseError {
+	fn new(input: &str, error: &VerboseError<&str>) -> Self {
+		// the first error is the innermost one, i.e. the one that actually failed
+		let (remaining, kind) = error
+			.errors
+			.first()
+			.cloned()
+			.unwrap_or((input, VerboseErrorKind::Context("anything")));
+		let offset = input.len() - remaining.len();
+		let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+
+		let expected = match kind {
+			VerboseErrorKind::Char(c) => format!("{c:?}"),
+			VerboseErrorKind::Context(context) => context.to_string(),
+			VerboseErrorKind::Nom(kind) => kind.description().to_lowercase(),
+		};
+		let found = match remaining.chars().next() {
+			Some(c) => format!("{c:?}"),
+			None => "the end of the input".to_string(),
+		};
+		ParseError {
+			line: input[..line_start].matches('\n').count() + 1,
+			column: input[line_start..offset].chars().count() + 1,
+			expected,
+			found,
+			text: input[line_start..].lines().next().unwrap_or_default().to_string(),
+		}
+	}
+}
+
+impl Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let ParseError {
+			line,
+			column,
+			expected,
+			found,
+			text,
+		} = self;
+		write!(
+			f,
+			"line {line}, column {column}: expected {expected}, found {found}\n{text}\n{:>column$}",
+			"^"
+		)
+	}
+}
+
+impl Error for ParseError {}
+
+/// Runs `parser` on the whole `input`. Errors show the line that couldn't be parsed, with a caret
+/// under the column.
+fn parse_all<'a, T>(
+	input: &'a str,
+	parser: impl FnMut(&'a str) -> ParseResult<'a, T>,
+) -> Result<T> {
+	let (_, value) = all_consuming(parser)(input)
+		.finish()
+		.map_err(|error| ParseError::new(input, &error))?;
+	Ok(value)
+}
+
+fn read_segmented<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>>
+where
+	<T as FromStr>::Err: 'static + Error + Send + Sync,
+{
+	Sections::new(input).repeat(|sections| {
+		sections.lines("segment", |line| Ok(line.trim().parse()?))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn every_day_is_registered_once() -> Result<()> {
+		let days: Vec<_> = solutions()?
+			.iter()
+			.map(|solution| (solution.year, solution.day))
+			.collect();
+		assert_eq!(days, (1..=25).map(|day| (2022, day)).collect::<Vec<_>>());
+		Ok(())
+	}
+
+	#[test]
+	fn days_are_formatted_as_runs() {
+		struct Nothing;
+
+		impl Puzzle for Nothing {
+			type Parsed = ();
+
+			fn parse(_input: &str) -> Result<()> {
+				Ok(())
+			}
+
+			fn part_1(_parsed: &()) -> Result<Answer> {
+				Ok(0.into())
+			}
+		}
+
+		let solutions = [
+			(2021, 24),
+			(2021, 25),
+			(2022, 1),
+			(2022, 2),
+			(2022, 3),
+			(2022, 5),
+		]
+		.map(|(year, day)| Solution::new::<Nothing>(year, day, "Nothing"));
+		let solutions: Vec<_> = solutions.iter().collect();
+		assert_eq!(format_days(&solutions), "2021/24-25, 2022/1-3, 2022/5");
+		assert_eq!(format_days(&[]), "none");
+	}
+
+	#[test]
+	fn parse_errors_say_where() {
+		let error = try_for_each_line("1\n2\nx\n", |line| {
+			Ok(line.trim().parse::<u8>().map(drop)?)
+		})
+		.unwrap_err();
+		assert_eq!(
+			format!("{error:#}"),
+			"line 3: invalid digit found in string"
+		);
+
+		let error = parse_all("1,2\n3;4", |i| {
+			nom::multi::separated_list1(
+				nom::character::complete::line_ending,
+				nom::sequence::separated_pair(
+					nom::character::complete::digit1,
+					nom::combinator::cut(nom::character::complete::char(',')),
+					nom::character::complete::digit1,
+				),
+			)(i)
+		})
+		.unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"line 2, column 2: expected ',', found ';'\n3;4\n ^"
+		);
+	}
+}
@@ -0,0 +1,43 @@
+use color_eyre::eyre::{Result, WrapErr};
+
+/// The outcome of checking one assumption a solution makes about its input, like the monkeys of
+/// day 11 being sorted. See [`Puzzle::lint`](crate::Puzzle::lint).
+pub struct Check {
+	pub name: &'static str,
+	pub result: Result<()>,
+}
+
+impl Check {
+	pub fn new(name: &'static str, result: Result<()>) -> Self {
+		Check { name, result }
+	}
+}
+
+/// Fails with the first check that failed, for the assumptions that parsing can't do without.
+pub fn require(checks: Vec<Check>) -> Result<()> {
+	for Check { name, result } in checks {
+		result.wrap_err_with(|| format!("expected that {name}"))?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use color_eyre::eyre::eyre;
+
+	use super::*;
+
+	#[test]
+	fn the_first_failure_is_required() {
+		let checks = vec![
+			Check::new("this holds", Ok(())),
+			Check::new("this doesn't", Err(eyre!("it doesn't"))),
+			Check::new("neither does this", Err(eyre!("not at all"))),
+		];
+		let error = require(checks).unwrap_err();
+		assert_eq!(
+			format!("{error:#}"),
+			"expected that this doesn't: it doesn't"
+		);
+	}
+}
@@ -1,108 +1,1014 @@
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
-use std::path::Path;
-use std::str::FromStr;
-
-use color_eyre::eyre::Result;
-use itertools::Itertools;
-use linkme::distributed_slice;
-use ndarray::{prelude::*, ErrorKind::IncompatibleShape, ShapeError};
-
-mod solutions {
-	automod::dir!("src/solutions");
-}
-
-#[distributed_slice]
-static SOLUTIONS: [(usize, fn() -> Result<()>)] = [..];
-
-fn try_for_each_line_in_file<P, F>(file_name: P, function: F) -> Result<()>
-where
-	P: AsRef<Path>,
-	F: FnMut(&str) -> Result<()>,
-{
-	fn try_for_each_line_in_file_impl<F>(file_name: &Path, mut function: F) -> Result<()>
-	where
-		F: FnMut(&str) -> Result<()>,
-	{
-		let file = File::open(file_name)?;
-		let mut file = BufReader::new(file);
-		let mut line = String::new();
-
-		while file.read_line(&mut line)? != 0 {
-			function(&line)?;
-			line.clear();
-		}
-
-		Ok(())
-	}
-
-	try_for_each_line_in_file_impl(file_name.as_ref(), function)
-}
-
-fn read_segmented<T: FromStr, P: AsRef<Path>>(file_name: P) -> Result<Vec<Vec<T>>>
-where
-	<T as FromStr>::Err: 'static + Error + Send + Sync,
-{
-	let mut result = Vec::new();
-	let mut new_segment = true;
-	try_for_each_line_in_file(file_name, |line| {
-		let line = line.trim();
-		if line.is_empty() {
-			new_segment = true;
-			return Ok(());
-		}
-		if new_segment {
-			new_segment = false;
-			result.push(Vec::new());
-		}
-		let segment = result.last_mut().expect("unreachable");
-		segment.push(line.parse()?);
-		Ok(())
-	})?;
-	Ok(result)
-}
-
-fn read_digit_field(file_name: &str) -> Result<Array2<u8>> {
-	let mut bytes = Vec::new();
-	File::open(file_name)?.read_to_end(&mut bytes)?;
-	let mut lines = 0usize;
-	let mut columns = None;
-	let values = bytes
-		.split(|&b| b == b'\n')
-		.map(|line| {
-			if line.is_empty() {
-				return Ok(line);
-			}
-			lines += 1;
-			if let Some(columns) = columns {
-				if columns != line.len() {
-					return Err(ShapeError::from_kind(IncompatibleShape).into());
-				}
-			}
-			columns = Some(line.len());
-			Ok(line)
-		})
-		.flatten_ok()
-		.map_ok(|&b| b.checked_sub(b'0').unwrap())
-		.collect::<Result<Vec<_>>>()?;
-	Ok(Array2::from_shape_vec((lines, columns.unwrap()), values)?)
-}
-
-fn main() -> Result<()> {
-	color_eyre::install()?;
-
-	let day = match std::env::args().nth(1) {
-		Some(day) => day.parse::<usize>()?,
-		None => {
-			eprintln!("please pass the day");
-			std::process::exit(1);
-		}
-	};
-
-	SOLUTIONS
-		.iter()
-		.find(|(i, _)| *i == day)
-		.unwrap_or_else(|| todo!("day {day} not implemented!"))
-		.1()
-}
+use std::io::{IsTerminal, Read};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use cpu_time::ProcessTime;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use advent_of_code_2022::fetch::Fetcher;
+use advent_of_code_2022::lint::Check;
+use advent_of_code_2022::runner::{run, run_parallel, with_timeout, DayRun, TimedOut};
+use advent_of_code_2022::{answers, bench, generate, scaffold, watch};
+use advent_of_code_2022::{format_days, solutions, Answer, Solution};
+
+#[derive(Clone, Debug)]
+enum Days {
+	Single(usize),
+	Range(RangeInclusive<usize>),
+}
+
+/// Days to run, optionally restricted to a year, e.g. `17`, `2022/17`, `2022/all` or `1..=10`.
+#[derive(Clone, Debug)]
+struct Selection {
+	year: Option<usize>,
+	days: Days,
+}
+
+impl FromStr for Selection {
+	type Err = String;
+
+	fn from_str(argument: &str) -> Result<Self, Self::Err> {
+		let parse_number = |number: &str, what: &str| {
+			number
+				.parse::<usize>()
+				.map_err(|_| format!("{number:?} is not a {what} number"))
+		};
+		let parse_day = |day: &str| parse_number(day, "day");
+
+		let (year, days) = match argument.split_once('/') {
+			Some((year, days)) => (Some(parse_number(year, "year")?), days),
+			None => (None, argument),
+		};
+		let days = if days == "all" {
+			Days::Range(0..=usize::MAX)
+		} else if let Some((first, last)) = days.split_once("..=") {
+			Days::Range(parse_day(first)?..=parse_day(last)?)
+		} else if let Some((first, end)) = days.split_once("..") {
+			let last = parse_day(end)?
+				.checked_sub(1)
+				.ok_or_else(|| format!("empty day range {argument}"))?;
+			Days::Range(parse_day(first)?..=last)
+		} else {
+			Days::Single(parse_day(days)?)
+		};
+		Ok(Selection { year, days })
+	}
+}
+
+impl Selection {
+	fn solutions(&self) -> Result<Vec<&'static Solution>> {
+		let solutions = solutions()?;
+		let days = match &self.days {
+			Days::Single(day) => *day..=*day,
+			Days::Range(days) => days.clone(),
+		};
+		let selected: Vec<_> = solutions
+			.iter()
+			.filter(|solution| self.year.is_none_or(|year| year == solution.year))
+			.filter(|solution| days.contains(&solution.day))
+			.copied()
+			.collect();
+		match (&self.days, selected.as_slice()) {
+			(_, []) => Err(eyre!(
+				"no registered day was selected, available days are {}",
+				format_days(&solutions)
+			)),
+			(Days::Single(day), [first, ..]) if selected.len() > 1 => Err(eyre!(
+				"day {day} exists in several years, select one like {}",
+				first.name()
+			)),
+			_ => Ok(selected),
+		}
+	}
+}
+
+fn parse_part(argument: &str) -> Result<usize, String> {
+	match argument {
+		"1" => Ok(1),
+		"2" => Ok(2),
+		_ => Err("there are only parts 1 and 2".to_string()),
+	}
+}
+
+fn parse_timeout(argument: &str) -> Result<Duration, String> {
+	argument
+		.parse()
+		.ok()
+		.and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+		.ok_or_else(|| format!("{argument:?} is not a number of seconds"))
+}
+
+/// How to run the days, for every command that runs them.
+#[derive(Args, Clone, Copy)]
+struct RunArgs {
+	/// Only run this part, the input is parsed either way
+	#[arg(short, long, value_parser = parse_part)]
+	part: Option<usize>,
+
+	/// Give up on parts still running after this many seconds, if they check for it (currently
+	/// only the slow searches of days 15, 16, 19 and 24 do)
+	#[arg(short, long, value_parser = parse_timeout)]
+	timeout: Option<Duration>,
+}
+
+#[derive(Parser)]
+#[command(
+	about = "Advent of Code solutions",
+	args_conflicts_with_subcommands = true,
+	arg_required_else_help = true
+)]
+struct Cli {
+	/// A single day, `all`, or a range of days like `1..=10`, optionally prefixed with the year
+	/// like `2022/17`
+	days: Option<Selection>,
+
+	/// Read the input from this path instead of `inputs/YYYY/day-XX`, or from stdin for `-`
+	#[arg(short, long)]
+	input: Option<String>,
+
+	#[command(flatten)]
+	args: RunArgs,
+
+	/// Print the answers as a table, as one JSON object per day, or as CSV
+	#[arg(short, long, value_enum, default_value_t = Format::Text)]
+	format: Format,
+
+	/// Run the days and their parts in parallel, only for more than one day
+	#[arg(long)]
+	parallel: bool,
+
+	#[command(subcommand)]
+	command: Option<Command>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+	Text,
+	Json,
+	Csv,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// List the registered days with their titles and inputs
+	List,
+	/// Check the answers against the ones recorded in `answers/YYYY/day-XX.toml`
+	Verify {
+		/// A single day, `all`, or a range of days like `1..=10`, optionally prefixed with the
+		/// year like `2022/17`
+		#[arg(default_value = "all")]
+		days: Selection,
+
+		#[command(flatten)]
+		args: RunArgs,
+	},
+	/// Check the assumptions the days make about their inputs, e.g. when a day fails for someone
+	/// else's input
+	Lint {
+		/// A single day, `all`, or a range of days like `1..=10`, optionally prefixed with the
+		/// year like `2022/17`
+		#[arg(default_value = "all")]
+		days: Selection,
+
+		/// Check this input instead of `inputs/YYYY/day-XX`, or the input from stdin for `-`
+		#[arg(short, long)]
+		input: Option<String>,
+	},
+	/// Run the days on every input in a directory, e.g. to compare the inputs of several people
+	Batch {
+		/// A single day, `all`, or a range of days like `1..=10`, optionally prefixed with the
+		/// year like `2022/17`
+		days: Selection,
+
+		/// The inputs of a single day, or the directory with a `YYYY/day-XX` directory of inputs
+		/// for each of the days
+		directory: PathBuf,
+
+		#[command(flatten)]
+		args: RunArgs,
+
+		/// Print the answers as a table, as one JSON object per input, or as CSV
+		#[arg(short, long, value_enum, default_value_t = Format::Text)]
+		format: Format,
+	},
+	/// Write generated inputs in the layout `batch` reads, e.g. to run it on them
+	Generate {
+		/// A single day, `all`, or a range of days like `1..=10`, optionally prefixed with the
+		/// year like `2022/17`
+		days: Selection,
+
+		/// Where to put the inputs of a single day, or a `YYYY/day-XX` directory of inputs for
+		/// each of the days
+		directory: PathBuf,
+
+		/// The seed of the first input, the others count up from it
+		#[arg(short, long, default_value_t = 1)]
+		seed: u64,
+
+		/// How many inputs to generate for every day
+		#[arg(short, long, default_value_t = 1)]
+		count: u64,
+
+		/// Roughly how many lines or items the inputs have, or how wide their maps are
+		#[arg(short = 'n', long, default_value_t = 100)]
+		size: usize,
+	},
+	/// Time the days over repeated runs, optionally comparing them to a saved baseline
+	Bench {
+		/// A single day, `all`, or a range of days like `1..=10`, optionally prefixed with the
+		/// year like `2022/17`
+		#[arg(default_value = "all")]
+		days: Selection,
+
+		/// Only time this part, parsing is timed either way
+		#[arg(short, long, value_parser = parse_part)]
+		part: Option<usize>,
+
+		/// How many times to run each step
+		#[arg(short, long, default_value_t = 10)]
+		runs: usize,
+
+		/// How many untimed runs of each step come first
+		#[arg(short, long, default_value_t = 1)]
+		warmup: usize,
+
+		/// Compare the medians to a baseline saved with `--save`
+		#[arg(short, long)]
+		baseline: Option<PathBuf>,
+
+		/// Save the statistics as a baseline, adding them to any that is already there
+		#[arg(short, long)]
+		save: Option<PathBuf>,
+
+		/// How many percent slower than the baseline counts as a regression
+		#[arg(short, long, default_value_t = 10.0)]
+		threshold: f64,
+	},
+	/// Run a day again whenever its input or one of the examples changes
+	Watch {
+		/// The day, optionally prefixed with the year like `2022/17`
+		day: Selection,
+
+		/// Also run the day on this file, may be given several times
+		#[arg(short, long)]
+		example: Vec<PathBuf>,
+
+		#[command(flatten)]
+		args: RunArgs,
+	},
+	/// Download the missing inputs, using the session cookie in `AOC_SESSION`
+	Fetch {
+		/// A single day, `all`, or a range of days like `1..=10`, optionally prefixed with the
+		/// year like `2022/17`
+		#[arg(default_value = "all")]
+		days: Selection,
+	},
+	/// Create `src/solutions/day_XX.rs` from a template, along with an empty input
+	NewDay {
+		day: usize,
+
+		/// The title of the puzzle, defaults to `Day XX`
+		title: Option<String>,
+	},
+}
+
+fn read_input(path: &str) -> Result<String> {
+	let mut input = String::new();
+	if path == "-" {
+		std::io::stdin()
+			.read_to_string(&mut input)
+			.wrap_err("failed to read input from stdin")?;
+	} else {
+		input = std::fs::read_to_string(path).wrap_err_with(|| format!("failed to read {path}"))?;
+	}
+	Ok(input)
+}
+
+fn default_input_path(solution: &Solution) -> String {
+	format!("inputs/{}/day-{:02}", solution.year, solution.day)
+}
+
+/// Offers to download a missing input, as long as there is someone to ask.
+fn offer_fetch(solution: &Solution) -> Result<()> {
+	let input_path = default_input_path(solution);
+	if Path::new(&input_path).exists() || !std::io::stdin().is_terminal() {
+		return Ok(());
+	}
+
+	eprint!("{input_path} is missing, download it? [y/N] ");
+	let mut reply = String::new();
+	std::io::stdin().read_line(&mut reply)?;
+	if matches!(reply.trim(), "y" | "Y" | "yes") {
+		Fetcher::from_env()?.fetch(solution.year, solution.day)?;
+	}
+	Ok(())
+}
+
+fn format_answer(answer: &Result<Answer>) -> String {
+	match answer {
+		Ok(answer) => {
+			let answer = answer.to_string();
+			match answer.lines().count() {
+				0 | 1 => answer,
+				lines => format!("[{lines} lines]"),
+			}
+		}
+		Err(report) if report.is::<TimedOut>() => "timed out".to_string(),
+		Err(_) => "failed".to_string(),
+	}
+}
+
+/// Prints the rows below the header with right-aligned columns.
+fn print_aligned(header: &[String], rows: &[Vec<String>]) {
+	let mut widths: Vec<_> = header.iter().map(|cell| cell.len()).collect();
+	for row in rows {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = (*width).max(cell.len());
+		}
+	}
+	for row in std::iter::once(header).chain(rows.iter().map(Vec::as_slice)) {
+		let cells = row
+			.iter()
+			.zip(&widths)
+			.map(|(cell, &width)| format!("{cell:>width$}"))
+			.collect::<Vec<_>>();
+		println!("{}", cells.join(" | ").trim_end());
+	}
+}
+
+fn format_bytes(bytes: u64) -> String {
+	let mut size = bytes as f64;
+	for unit in ["B", "KiB", "MiB"] {
+		if size < 1024.0 {
+			return if unit == "B" {
+				format!("{bytes} B")
+			} else {
+				format!("{size:.1} {unit}")
+			};
+		}
+		size /= 1024.0;
+	}
+	format!("{size:.1} GiB")
+}
+
+/// Prints what was allocated by every step, if the allocations were counted.
+fn print_allocations(label_header: &[&str], runs: &[(Vec<String>, &DayRun)]) {
+	let mut rows = Vec::new();
+	for (labels, run) in runs {
+		let steps = std::iter::once(("parse".to_string(), run.parse_allocations)).chain(
+			run.parts
+				.iter()
+				.map(|part| (format!("part {}", part.part), part.allocations)),
+		);
+		for (step, allocations) in steps {
+			if let Some(allocations) = allocations {
+				let cells = [
+					step,
+					allocations.allocations.to_string(),
+					format_bytes(allocations.bytes),
+					format_bytes(allocations.peak_bytes),
+				];
+				rows.push(labels.iter().cloned().chain(cells).collect());
+			}
+		}
+	}
+	if rows.is_empty() {
+		return;
+	}
+
+	let header: Vec<String> = label_header
+		.iter()
+		.chain(&["step", "allocations", "bytes", "peak"])
+		.map(|cell| cell.to_string())
+		.collect();
+	println!();
+	print_aligned(&header, &rows);
+}
+
+/// Prints the runs as a table, each led by its label cells (like the day), followed by the
+/// multi-line answers and errors that don't fit into it.
+fn print_table(label_header: &[&str], runs: &[(Vec<String>, &DayRun)]) {
+	let header: Vec<String> = label_header
+		.iter()
+		.chain(&["part 1", "part 2", "parse", "part 1", "part 2"])
+		.map(|cell| cell.to_string())
+		.collect();
+	let rows: Vec<Vec<String>> = runs
+		.iter()
+		.map(|(labels, run)| {
+			let missing = if run.parse.is_ok() { "-" } else { "failed" };
+			let answer = |part: usize| {
+				run.part(part)
+					.map_or_else(|| missing.to_string(), |part| format_answer(&part.answer))
+			};
+			let time = |part: usize| {
+				run.part(part)
+					.map_or_else(|| "-".to_string(), |part| format!("{:.1?}", part.time))
+			};
+			let parse = match &run.parse {
+				Ok(time) => format!("{time:.1?}"),
+				Err(_) => "failed".to_string(),
+			};
+			let cells = [answer(1), answer(2), parse, time(1), time(2)];
+			labels.iter().cloned().chain(cells).collect()
+		})
+		.collect();
+
+	print_aligned(&header, &rows);
+	print_allocations(label_header, runs);
+
+	for (labels, run) in runs {
+		let label = labels.join(" ");
+		for part in &run.parts {
+			match &part.answer {
+				Ok(answer) if answer.to_string().lines().count() > 1 => {
+					println!("\n{label} part {}:\n{answer}", part.part)
+				}
+				Ok(_) => {}
+				Err(report) => eprintln!("\n{label} part {} failed: {report:#}", part.part),
+			}
+		}
+		if let Err(report) = &run.parse {
+			eprintln!("\n{label} failed: {report:#}");
+		}
+	}
+}
+
+fn csv_field(field: &str) -> String {
+	if field.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
+
+fn error_cell<T>(result: &Result<T>) -> String {
+	result
+		.as_ref()
+		.err()
+		.map_or(String::new(), |report| format!("{report:#}"))
+}
+
+/// Prints one line per run and input, with the same columns as the JSON output.
+fn print_csv<'a>(runs: impl IntoIterator<Item = (&'a str, &'a DayRun)>) {
+	let header = [
+		"year",
+		"day",
+		"input",
+		"parse_nanos",
+		"error",
+		"part_1",
+		"part_1_nanos",
+		"part_1_error",
+		"part_2",
+		"part_2_nanos",
+		"part_2_error",
+	];
+	println!("{}", header.join(","));
+
+	for (input, run) in runs {
+		let mut row = vec![
+			run.year.to_string(),
+			run.day.to_string(),
+			input.to_string(),
+			run.parse
+				.as_ref()
+				.map_or(String::new(), |time| time.as_nanos().to_string()),
+			error_cell(&run.parse),
+		];
+		for number in [1, 2] {
+			match run.part(number) {
+				Some(part) => row.extend([
+					part.answer
+						.as_ref()
+						.map_or(String::new(), Answer::to_string),
+					part.time.as_nanos().to_string(),
+					error_cell(&part.answer),
+				]),
+				None => row.extend([String::new(), String::new(), String::new()]),
+			}
+		}
+		let row: Vec<_> = row.iter().map(|field| csv_field(field)).collect();
+		println!("{}", row.join(","));
+	}
+}
+
+fn run_day(solution: &Solution, input_path: &str, args: RunArgs, parallel: bool) -> DayRun {
+	match read_input(input_path) {
+		Ok(input) if parallel => {
+			run_parallel(solution, &input, args.part, args.timeout).with_input_path(input_path)
+		}
+		Ok(input) => run(solution, &input, args.part, args.timeout).with_input_path(input_path),
+		Err(report) => DayRun::failed(solution, report),
+	}
+}
+
+/// Runs the days on their default inputs, returning each run along with the path of its input.
+fn run_days(solutions: &[&Solution], args: RunArgs, parallel: bool) -> Vec<(String, DayRun)> {
+	// ask about every missing input up front, rather than from several threads at once
+	let fetched: Vec<_> = solutions
+		.iter()
+		.map(|solution| (*solution, offer_fetch(solution)))
+		.collect();
+	let run = |(solution, fetched): (&Solution, Result<()>)| {
+		let input_path = default_input_path(solution);
+		let run = match fetched {
+			Ok(()) => run_day(solution, &input_path, args, parallel),
+			Err(report) => DayRun::failed(solution, report),
+		};
+		(input_path, run)
+	};
+	if parallel {
+		fetched.into_par_iter().map(run).collect()
+	} else {
+		fetched.into_iter().map(run).collect()
+	}
+}
+
+fn print_json(runs: impl IntoIterator<Item = impl Serialize>) -> Result<()> {
+	for run in runs {
+		println!("{}", serde_json::to_string(&run)?);
+	}
+	Ok(())
+}
+
+fn print_runs(runs: &[(String, DayRun)], format: Format) -> Result<()> {
+	match format {
+		Format::Text => print_table(
+			&["day"],
+			&runs
+				.iter()
+				.map(|(_, run)| (vec![run.name()], run))
+				.collect::<Vec<_>>(),
+		),
+		Format::Json => print_json(runs.iter().map(|(_, run)| run))?,
+		Format::Csv => print_csv(runs.iter().map(|(input, run)| (input.as_str(), run))),
+	}
+	Ok(())
+}
+
+fn run_range(solutions: &[&Solution], args: RunArgs, format: Format, parallel: bool) -> Result<()> {
+	let start = Instant::now();
+	// summed over every thread, so it can be compared to the wall time
+	let cpu_start = ProcessTime::now();
+	let runs = run_days(solutions, args, parallel);
+	let wall_time = start.elapsed();
+	let cpu_time = cpu_start.elapsed();
+	print_runs(&runs, format)?;
+
+	if parallel && format == Format::Text {
+		println!("\ntook {wall_time:.1?}, {cpu_time:.1?} of CPU time");
+	}
+
+	if runs.iter().all(|(_, run)| run.succeeded()) {
+		Ok(())
+	} else {
+		Err(eyre!("some days failed"))
+	}
+}
+
+/// A run on one of the inputs of a batch.
+#[derive(Serialize)]
+struct InputRun<'a> {
+	input: &'a str,
+	#[serde(flatten)]
+	run: &'a DayRun,
+}
+
+/// The files in a directory by name, without hidden ones like `.gitkeep`.
+fn input_files(directory: &Path) -> Result<Vec<PathBuf>> {
+	let entries = std::fs::read_dir(directory)
+		.wrap_err_with(|| format!("failed to read {}", directory.display()))?;
+	let mut files = Vec::new();
+	for entry in entries {
+		let entry = entry?;
+		let hidden = entry.file_name().to_string_lossy().starts_with('.');
+		if !hidden && entry.file_type()?.is_file() {
+			files.push(entry.path());
+		}
+	}
+	files.sort();
+	Ok(files)
+}
+
+fn batch(selection: &Selection, directory: &Path, args: RunArgs, format: Format) -> Result<()> {
+	let mut runs = Vec::new();
+	for solution in selection.solutions()? {
+		let inputs = match selection.days {
+			Days::Single(_) => input_files(directory)?,
+			Days::Range(_) => {
+				let day_directory =
+					directory.join(format!("{}/day-{:02}", solution.year, solution.day));
+				if !day_directory.is_dir() {
+					continue;
+				}
+				input_files(&day_directory)?
+			}
+		};
+		for input in inputs {
+			let input = input.display().to_string();
+			let run = run_day(solution, &input, args, false);
+			runs.push((input, run));
+		}
+	}
+	if runs.is_empty() {
+		return Err(eyre!("there are no inputs in {}", directory.display()));
+	}
+
+	match format {
+		Format::Text => {
+			let file_name = |input: &str| {
+				Path::new(input)
+					.file_name()
+					.map_or(input.to_string(), |name| {
+						name.to_string_lossy().into_owned()
+					})
+			};
+			print_table(
+				&["day", "input"],
+				&runs
+					.iter()
+					.map(|(input, run)| (vec![run.name(), file_name(input)], run))
+					.collect::<Vec<_>>(),
+			)
+		}
+		Format::Json => print_json(runs.iter().map(|(input, run)| InputRun { input, run }))?,
+		Format::Csv => print_csv(runs.iter().map(|(input, run)| (input.as_str(), run))),
+	}
+
+	match runs.iter().filter(|(_, run)| !run.succeeded()).count() {
+		0 => Ok(()),
+		failed => Err(eyre!("failed on {failed} of {} inputs", runs.len())),
+	}
+}
+
+fn generate_inputs(
+	selection: &Selection,
+	directory: &Path,
+	seed: u64,
+	count: u64,
+	size: usize,
+) -> Result<()> {
+	for solution in selection.solutions()? {
+		let day_directory = match selection.days {
+			Days::Single(_) => directory.to_path_buf(),
+			Days::Range(_) => directory.join(format!("{}/day-{:02}", solution.year, solution.day)),
+		};
+		std::fs::create_dir_all(&day_directory)
+			.wrap_err_with(|| format!("failed to create {}", day_directory.display()))?;
+		for seed in seed..seed + count {
+			let input = generate::generate(solution.year, solution.day, seed, size)
+				.wrap_err_with(|| format!("failed to generate an input for {}", solution.name()))?;
+			let path = day_directory.join(format!("size-{size}-seed-{seed}"));
+			std::fs::write(&path, input)
+				.wrap_err_with(|| format!("failed to write {}", path.display()))?;
+			println!("{:>7}  {}", solution.name(), path.display());
+		}
+	}
+	Ok(())
+}
+
+fn describe_mismatch(part_number: usize, expected: &Answer, actual: &Answer) -> String {
+	let describe = |answer: &Answer| match answer {
+		Answer::Integer(integer) => integer.to_string(),
+		Answer::Text(text) => format!("{text:?}"),
+	};
+	let multi_line = |answer: &Answer| matches!(answer, Answer::Text(text) if text.contains('\n'));
+	if multi_line(expected) || multi_line(actual) {
+		format!("part {part_number} expected\n{expected}\nbut got\n{actual}")
+	} else {
+		format!(
+			"part {part_number} expected {} but got {}",
+			describe(expected),
+			describe(actual)
+		)
+	}
+}
+
+fn verify_day(run: &DayRun, selected_part: Option<usize>) -> Result<()> {
+	let expected = answers::load(run.year, run.day)?;
+	if let Err(report) = &run.parse {
+		return Err(eyre!("{report:#}"));
+	}
+
+	let mut mismatches = Vec::new();
+	for (part_number, expected) in [(1, Some(expected.part1)), (2, expected.part2)] {
+		if selected_part.is_some_and(|selected| selected != part_number) {
+			continue;
+		}
+		match (expected, run.part(part_number).map(|part| &part.answer)) {
+			(Some(expected), Some(Ok(actual))) if expected == *actual => {}
+			(Some(expected), Some(Ok(actual))) => {
+				mismatches.push(describe_mismatch(part_number, &expected, actual))
+			}
+			(_, Some(Err(report))) => {
+				mismatches.push(format!("part {part_number} failed: {report:#}"))
+			}
+			(Some(_), None) => mismatches.push(format!(
+				"part {part_number} has a recorded answer but no solution"
+			)),
+			(None, Some(_)) => {
+				mismatches.push(format!("part {part_number} has no recorded answer"))
+			}
+			(None, None) => {}
+		}
+	}
+
+	if mismatches.is_empty() {
+		Ok(())
+	} else {
+		Err(eyre!(mismatches.join("\n")))
+	}
+}
+
+fn verify(selection: &Selection, args: RunArgs) -> Result<()> {
+	let mut failed = 0;
+	for (_, run) in run_days(&selection.solutions()?, args, false) {
+		match verify_day(&run, args.part) {
+			Ok(()) => println!("{:>7}: ok", run.name()),
+			Err(report) => {
+				failed += 1;
+				println!("{:>7}: {report:#}", run.name());
+			}
+		}
+	}
+
+	match failed {
+		0 => Ok(()),
+		1 => Err(eyre!("1 day does not match its recorded answers")),
+		failed => Err(eyre!("{failed} days do not match their recorded answers")),
+	}
+}
+
+fn lint(selection: &Selection, input_path: Option<String>) -> Result<()> {
+	let solutions = selection.solutions()?;
+	if input_path.is_some() && solutions.len() > 1 {
+		return Err(eyre!("--input can only be used with a single day"));
+	}
+
+	let mut failed = 0;
+	for solution in solutions {
+		let input_path = match &input_path {
+			Some(input_path) => input_path.clone(),
+			None => {
+				offer_fetch(solution)?;
+				default_input_path(solution)
+			}
+		};
+		let checks = read_input(&input_path).and_then(|input| {
+			(solution.lint)(&input).wrap_err_with(|| format!("failed to parse {input_path}"))
+		});
+		let checks = match checks {
+			Ok(checks) if checks.is_empty() => {
+				println!("{:>7}: no checks", solution.name());
+				continue;
+			}
+			Ok(checks) => checks,
+			Err(report) => {
+				failed += 1;
+				println!("{:>7}: {report:#}", solution.name());
+				continue;
+			}
+		};
+		// every check is reported, not just the first one that failed
+		let mut day_failed = false;
+		for Check { name, result } in checks {
+			match result {
+				Ok(()) => println!("{:>7}: ok      {name}", solution.name()),
+				Err(report) => {
+					day_failed = true;
+					println!("{:>7}: failed  {name}: {report:#}", solution.name());
+				}
+			}
+		}
+		failed += usize::from(day_failed);
+	}
+
+	match failed {
+		0 => Ok(()),
+		1 => Err(eyre!("1 day doesn't fit its input")),
+		failed => Err(eyre!("{failed} days don't fit their inputs")),
+	}
+}
+
+fn list() -> Result<()> {
+	for solution in solutions()? {
+		let input_path = default_input_path(solution);
+		let missing = if Path::new(&input_path).exists() {
+			""
+		} else {
+			" (missing)"
+		};
+		println!(
+			"{:>7}  {:<30}  {input_path}{missing}",
+			solution.name(),
+			solution.title
+		);
+	}
+	Ok(())
+}
+
+fn benchmark(
+	selection: &Selection,
+	part: Option<usize>,
+	runs: usize,
+	warmup: usize,
+	baseline: Option<&Path>,
+	save: Option<&Path>,
+	threshold: f64,
+) -> Result<()> {
+	let baseline = baseline.map(bench::load_baseline).transpose()?;
+	let time = |nanos: u64| format!("{:.1?}", Duration::from_nanos(nanos));
+
+	let mut benches = Vec::new();
+	let mut rows = Vec::new();
+	let mut regressions = 0;
+	for solution in selection.solutions()? {
+		offer_fetch(solution)?;
+		let input = read_input(&default_input_path(solution))?;
+		let day = bench::measure(solution, &input, warmup, runs, part)
+			.wrap_err_with(|| format!("{} failed", solution.name()))?;
+		for (step, stats) in &day.steps {
+			let before = baseline
+				.as_ref()
+				.and_then(|baseline| baseline.get(&day.name())?.get(step));
+			let (before, change) = match before {
+				Some(before) => {
+					let change = stats.change(before);
+					let regressed = change * 100.0 > threshold;
+					regressions += usize::from(regressed);
+					(
+						time(before.median_nanos),
+						format!(
+							"{:+.1}%{}",
+							change * 100.0,
+							if regressed { " (regressed)" } else { "" }
+						),
+					)
+				}
+				None => ("-".to_string(), "-".to_string()),
+			};
+			rows.push(vec![
+				day.name(),
+				step.clone(),
+				time(stats.min_nanos),
+				time(stats.median_nanos),
+				time(stats.p95_nanos),
+				before,
+				change,
+			]);
+		}
+		benches.push(day);
+	}
+
+	let header = ["day", "step", "min", "median", "p95", "baseline", "change"].map(String::from);
+	print_aligned(&header, &rows);
+	if let Some(save) = save {
+		bench::save_baseline(save, &benches)?;
+	}
+
+	match regressions {
+		0 => Ok(()),
+		1 => Err(eyre!("1 step got more than {threshold}% slower")),
+		regressions => Err(eyre!(
+			"{regressions} steps got more than {threshold}% slower"
+		)),
+	}
+}
+
+fn watch_day(selection: &Selection, examples: Vec<PathBuf>, args: RunArgs) -> Result<()> {
+	let solution = match (&selection.days, selection.solutions()?.as_slice()) {
+		(Days::Single(_), [solution]) => *solution,
+		_ => return Err(eyre!("only a single day can be watched")),
+	};
+	let mut paths = vec![PathBuf::from(default_input_path(solution))];
+	paths.extend(examples);
+	watch::watch(solution, &paths, args.part, args.timeout)
+}
+
+fn fetch(selection: &Selection) -> Result<()> {
+	let fetcher = Fetcher::from_env()?;
+	for solution in selection.solutions()? {
+		let input_path = fetcher.input_path(solution.year, solution.day);
+		if input_path.exists() {
+			println!("{:>7}  {} (cached)", solution.name(), input_path.display());
+		} else {
+			fetcher.fetch(solution.year, solution.day)?;
+			println!("{:>7}  {}", solution.name(), input_path.display());
+		}
+	}
+	Ok(())
+}
+
+fn new_day(day: usize, title: Option<String>) -> Result<()> {
+	let title = title.unwrap_or_else(|| format!("Day {day}"));
+	for path in scaffold::create_day(Path::new("."), day, &title)? {
+		println!("created {}", path.display());
+	}
+	Ok(())
+}
+
+fn main() -> Result<()> {
+	color_eyre::install()?;
+
+	let cli = Cli::parse();
+	match (cli.command, cli.days) {
+		(Some(Command::List), _) => list(),
+		(Some(Command::Verify { days, args }), _) => verify(&days, args),
+		(Some(Command::Lint { days, input }), _) => lint(&days, input),
+		(
+			Some(Command::Batch {
+				days,
+				directory,
+				args,
+				format,
+			}),
+			_,
+		) => batch(&days, &directory, args, format),
+		(
+			Some(Command::Generate {
+				days,
+				directory,
+				seed,
+				count,
+				size,
+			}),
+			_,
+		) => generate_inputs(&days, &directory, seed, count, size),
+		(
+			Some(Command::Bench {
+				days,
+				part,
+				runs,
+				warmup,
+				baseline,
+				save,
+				threshold,
+			}),
+			_,
+		) => benchmark(
+			&days,
+			part,
+			runs,
+			warmup,
+			baseline.as_deref(),
+			save.as_deref(),
+			threshold,
+		),
+		(Some(Command::Watch { day, example, args }), _) => watch_day(&day, example, args),
+		(Some(Command::Fetch { days }), _) => fetch(&days),
+		(Some(Command::NewDay { day, title }), _) => new_day(day, title),
+		(None, Some(selection)) => {
+			let solutions = selection.solutions()?;
+			let solution = match (&selection.days, solutions.as_slice()) {
+				(Days::Single(_), [solution]) => solution,
+				_ => {
+					if cli.input.is_some() {
+						return Err(eyre!("--input can only be used with a single day"));
+					}
+					return run_range(&solutions, cli.args, cli.format, cli.parallel);
+				}
+			};
+
+			if cli.parallel {
+				return Err(eyre!("--parallel is only for more than one day"));
+			}
+
+			let input_path = match cli.input {
+				Some(input_path) => input_path,
+				None => {
+					offer_fetch(solution)?;
+					default_input_path(solution)
+				}
+			};
+			if cli.format != Format::Text {
+				let run = run_day(solution, &input_path, cli.args, false);
+				let succeeded = run.succeeded();
+				print_runs(&[(input_path, run)], cli.format)?;
+				return if succeeded {
+					Ok(())
+				} else {
+					Err(eyre!("{} failed", solution.name()))
+				};
+			}
+
+			let parts = match cli.args.part {
+				Some(number) => vec![(number, solution.part(number)?)],
+				None => solution.parts().collect(),
+			};
+			let input = read_input(&input_path)?;
+			let parsed = (solution.parse)(&input)
+				.wrap_err_with(|| format!("failed to parse {input_path}"))?;
+			for (_, part) in parts {
+				println!("{}", with_timeout(cli.args.timeout, || part(&parsed))?);
+			}
+			Ok(())
+		}
+		(None, None) => Err(eyre!("please pass the day")),
+	}
+}
@@ -0,0 +1,260 @@
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt::Display;
+use std::panic::{catch_unwind, set_hook, take_hook, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Report, Result};
+use rayon::prelude::*;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::alloc::{self, AllocStats};
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::{Answer, Parsed, Solution};
+
+/// The error of a part that didn't finish in time.
+#[derive(Debug)]
+pub struct TimedOut(pub Duration);
+
+impl Display for TimedOut {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "timed out after {:.1?}", self.0)
+	}
+}
+
+impl Error for TimedOut {}
+
+/// Runs `function` with a [`CancellationToken`] that is cancelled after `timeout`, turning the
+/// resulting [`Cancelled`] error into [`TimedOut`].
+pub fn with_timeout<T>(
+	timeout: Option<Duration>,
+	function: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+	CancellationToken::with_timeout(timeout, function).map_err(|report| {
+		match (report.is::<Cancelled>(), timeout) {
+			(true, Some(timeout)) => TimedOut(timeout).into(),
+			_ => report,
+		}
+	})
+}
+
+pub struct PartRun {
+	pub part: usize,
+	pub answer: Result<Answer>,
+	pub time: Duration,
+	/// Only counted with the `alloc-stats` feature.
+	pub allocations: Option<AllocStats>,
+}
+
+pub struct DayRun {
+	pub year: usize,
+	pub day: usize,
+	pub parse: Result<Duration>,
+	pub parse_allocations: Option<AllocStats>,
+	pub parts: Vec<PartRun>,
+}
+
+impl DayRun {
+	pub fn failed(solution: &Solution, report: Report) -> Self {
+		DayRun {
+			year: solution.year,
+			day: solution.day,
+			parse: Err(report),
+			parse_allocations: None,
+			parts: Vec::new(),
+		}
+	}
+
+	pub fn name(&self) -> String {
+		format!("{}/{}", self.year, self.day)
+	}
+
+	/// Names the input in a parse error, for when it isn't obvious which one was read.
+	pub fn with_input_path(mut self, path: impl Display) -> Self {
+		self.parse = self
+			.parse
+			.map_err(|report| report.wrap_err(format!("failed to parse {path}")));
+		self
+	}
+
+	pub fn succeeded(&self) -> bool {
+		self.parse.is_ok() && self.parts.iter().all(|part| part.answer.is_ok())
+	}
+
+	pub fn part(&self, number: usize) -> Option<&PartRun> {
+		self.parts.iter().find(|part| part.part == number)
+	}
+}
+
+// errors are written as their whole chain of causes, without the backtrace and other sections
+fn error_message<T>(result: &Result<T>) -> Option<String> {
+	result.as_ref().err().map(|report| format!("{report:#}"))
+}
+
+impl Serialize for PartRun {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut part = serializer.serialize_struct("PartRun", 5)?;
+		part.serialize_field("part", &self.part)?;
+		part.serialize_field("answer", &self.answer.as_ref().ok())?;
+		part.serialize_field("nanos", &self.time.as_nanos())?;
+		part.serialize_field("error", &error_message(&self.answer))?;
+		if self.allocations.is_some() {
+			part.serialize_field("allocations", &self.allocations)?;
+		} else {
+			part.skip_field("allocations")?;
+		}
+		part.end()
+	}
+}
+
+impl Serialize for DayRun {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut day = serializer.serialize_struct("DayRun", 6)?;
+		day.serialize_field("year", &self.year)?;
+		day.serialize_field("day", &self.day)?;
+		day.serialize_field(
+			"parse_nanos",
+			&self.parse.as_ref().ok().map(Duration::as_nanos),
+		)?;
+		day.serialize_field("error", &error_message(&self.parse))?;
+		if self.parse_allocations.is_some() {
+			day.serialize_field("parse_allocations", &self.parse_allocations)?;
+		} else {
+			day.skip_field("parse_allocations")?;
+		}
+		day.serialize_field("parts", &self.parts)?;
+		day.end()
+	}
+}
+
+thread_local! {
+	static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// Turns a panic of `function` into an error. The panics caught here are reported along with the
+/// other failures, so the panic hook stays quiet about them, but not about panics anywhere else.
+pub(crate) fn catch_panic<T>(function: impl FnOnce() -> Result<T>) -> Result<T> {
+	QUIET_HOOK.call_once(|| {
+		let hook = take_hook();
+		set_hook(Box::new(move |info| {
+			if !CATCHING.get() {
+				hook(info);
+			}
+		}));
+	});
+	let catching = CATCHING.replace(true);
+	let result = catch_unwind(AssertUnwindSafe(function));
+	CATCHING.set(catching);
+	result.unwrap_or_else(|payload| {
+		let message = payload
+			.downcast_ref::<&str>()
+			.map(|message| message.to_string())
+			.or_else(|| payload.downcast_ref::<String>().cloned())
+			.unwrap_or_else(|| "unknown cause".to_string());
+		Err(eyre!("panicked: {message}"))
+	})
+}
+
+/// Parses the input once and runs either every part of the solution or only the selected one.
+/// Parts that check their [`CancellationToken`] give up once `timeout` passes.
+pub fn run(
+	solution: &Solution,
+	input: &str,
+	selected_part: Option<usize>,
+	timeout: Option<Duration>,
+) -> DayRun {
+	run_with(solution, input, selected_part, timeout, false)
+}
+
+/// Like [`run`], but runs the parts in parallel on the rayon pool. Allocations aren't counted,
+/// since everything else running at the same time would be counted along with them.
+pub fn run_parallel(
+	solution: &Solution,
+	input: &str,
+	selected_part: Option<usize>,
+	timeout: Option<Duration>,
+) -> DayRun {
+	run_with(solution, input, selected_part, timeout, true)
+}
+
+fn measure<T>(parallel: bool, function: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+	if parallel {
+		(function(), None)
+	} else {
+		alloc::measure(function)
+	}
+}
+
+fn run_with(
+	solution: &Solution,
+	input: &str,
+	selected_part: Option<usize>,
+	timeout: Option<Duration>,
+	parallel: bool,
+) -> DayRun {
+	// a day without the selected part (like day 25's part 2) just has nothing to run
+	let parts: Vec<_> = solution
+		.parts()
+		.filter(|(number, _)| selected_part.is_none_or(|selected| selected == *number))
+		.collect();
+
+	let start = Instant::now();
+	let (parsed, parse_allocations) = measure(parallel, || catch_panic(|| (solution.parse)(input)));
+	let parse_time = start.elapsed();
+	let parsed = match parsed {
+		Ok(parsed) => parsed,
+		Err(report) => return DayRun::failed(solution, report),
+	};
+
+	let run_part = |(number, part): (usize, fn(&Parsed) -> Result<Answer>)| {
+		let start = Instant::now();
+		let (answer, allocations) = measure(parallel, || {
+			with_timeout(timeout, || catch_panic(|| part(&parsed)))
+		});
+		PartRun {
+			part: number,
+			answer,
+			time: start.elapsed(),
+			allocations,
+		}
+	};
+	let parts = if parallel {
+		parts.into_par_iter().map(run_part).collect()
+	} else {
+		parts.into_iter().map(run_part).collect()
+	};
+
+	DayRun {
+		year: solution.year,
+		day: solution.day,
+		parse: Ok(parse_time),
+		parse_allocations,
+		parts,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::find_solution;
+
+	#[test]
+	fn json_has_typed_answers_and_errors() -> Result<()> {
+		let solution = find_solution(2022, 6)?;
+		let run = run(solution, "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n", Some(1), None);
+		let json = serde_json::to_value(&run)?;
+		assert_eq!(json["year"], 2022);
+		assert_eq!(json["day"], 6);
+		assert_eq!(json["error"], serde_json::Value::Null);
+		assert_eq!(json["parts"][0]["answer"], 7);
+
+		let run = DayRun::failed(solution, eyre!("no input"));
+		let json = serde_json::to_value(&run)?;
+		assert_eq!(json["parse_nanos"], serde_json::Value::Null);
+		assert_eq!(json["error"], "no input");
+		Ok(())
+	}
+}
@@ -0,0 +1,85 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::solutions::YEAR;
+
+const TEMPLATE: &str = include_str!("scaffold/day.rs.template");
+
+/// Fills in the day module template, with stubs for the parts and an (ignored) example test.
+pub fn render(day: usize, title: &str) -> String {
+	TEMPLATE
+		.replace("{{STRUCT}}", &format!("Day{day:02}"))
+		.replace("{{DAY}}", &day.to_string())
+		.replace("{{TITLE}}", &format!("{title:?}"))
+}
+
+/// Creates `src/solutions/day_XX.rs` and an empty input below `root` and returns the paths of
+/// the files it created. An existing module is never overwritten, an existing input is kept.
+pub fn create_day(root: &Path, day: usize, title: &str) -> Result<Vec<PathBuf>> {
+	if !(1..=25).contains(&day) {
+		return Err(eyre!("there is no day {day}, only days 1 to 25"));
+	}
+
+	let module_path = root.join(format!("src/solutions/day_{day:02}.rs"));
+	let mut module = OpenOptions::new()
+		.write(true)
+		.create_new(true)
+		.open(&module_path)
+		.map_err(|error| match error.kind() {
+			ErrorKind::AlreadyExists => eyre!("{} already exists", module_path.display()),
+			_ => eyre!(error).wrap_err(format!("failed to create {}", module_path.display())),
+		})?;
+	module
+		.write_all(render(day, title).as_bytes())
+		.wrap_err_with(|| format!("failed to write {}", module_path.display()))?;
+	let mut created = vec![module_path];
+
+	let input_path = root.join(format!("inputs/{YEAR}/day-{day:02}"));
+	if !input_path.exists() {
+		if let Some(directory) = input_path.parent() {
+			fs::create_dir_all(directory)
+				.wrap_err_with(|| format!("failed to create {}", directory.display()))?;
+		}
+		fs::write(&input_path, "")
+			.wrap_err_with(|| format!("failed to create {}", input_path.display()))?;
+		created.push(input_path);
+	}
+
+	Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn template_registers_the_day() {
+		let module = render(7, "No Space Left On Device");
+		assert!(module.contains("struct Day07;"));
+		assert!(module.contains(r#"solution!(Day07, day = 7, title = "No Space Left On Device");"#));
+		assert!(!module.contains("{{"));
+	}
+
+	#[test]
+	fn existing_days_are_not_overwritten() -> Result<()> {
+		let root = tempfile::tempdir()?;
+		fs::create_dir_all(root.path().join("src/solutions"))?;
+
+		let created = create_day(root.path(), 3, "Rucksack Reorganization")?;
+		assert_eq!(
+			created,
+			[
+				root.path().join("src/solutions/day_03.rs"),
+				root.path().join(format!("inputs/{YEAR}/day-03")),
+			]
+		);
+
+		fs::write(&created[0], "solved")?;
+		assert!(create_day(root.path(), 3, "Rucksack Reorganization").is_err());
+		assert_eq!(fs::read_to_string(&created[0])?, "solved");
+		Ok(())
+	}
+}
@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+
+use color_eyre::eyre::{eyre, Report, Result, WrapErr};
+
+use crate::grid::GridError;
+use crate::{Line, ParseError};
+
+/// One run of non-blank lines, without their line endings.
+struct Section<'a> {
+	/// The line number (counting from one) of the first line.
+	first_line: usize,
+	lines: Vec<&'a str>,
+}
+
+/// Puzzle input made of blocks separated by blank lines, like a drawing followed by moves.
+///
+/// A day reads its sections in order, each with its own parser:
+///
+/// - [`block`](Sections::block) hands the whole section to a parser, e.g. a [`Grid`] or nom,
+/// - [`lines`](Sections::lines) parses the section line by line,
+/// - [`repeat`](Sections::repeat) reads sections until there are none left.
+///
+/// Errors name the section that failed, and line numbers within it are counted from the start of
+/// the input rather than the section.
+///
+/// [`Grid`]: crate::grid::Grid
+pub struct Sections<'a> {
+	sections: VecDeque<Section<'a>>,
+}
+
+impl<'a> Sections<'a> {
+	pub fn new(input: &'a str) -> Self {
+		let mut sections = VecDeque::new();
+		let mut current: Option<Section> = None;
+		// `lines` takes care of CRLF line endings, and so do the parsers as the lines are joined
+		// back with plain newlines
+		for (number, line) in input.lines().enumerate() {
+			if line.trim().is_empty() {
+				sections.extend(current.take());
+				continue;
+			}
+			current
+				.get_or_insert_with(|| Section {
+					first_line: number + 1,
+					lines: Vec::new(),
+				})
+				.lines
+				.push(line);
+		}
+		sections.extend(current);
+		Sections { sections }
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.sections.is_empty()
+	}
+
+	fn next(&mut self, name: &str) -> Result<Section<'a>> {
+		self.sections
+			.pop_front()
+			.ok_or_else(|| eyre!("missing the {name}"))
+	}
+
+	/// Parses the next section as a whole. The text ends with a newline, like a file would.
+	pub fn block<T>(&mut self, name: &str, parse: impl FnOnce(&str) -> Result<T>) -> Result<T> {
+		let section = self.next(name)?;
+		let mut text = section.lines.join("\n");
+		text.push('\n');
+		parse(&text)
+			.map_err(|report| shift_lines(report, section.first_line - 1))
+			.wrap_err_with(|| in_section(name, &section))
+	}
+
+	/// Parses every line of the next section.
+	pub fn lines<T>(
+		&mut self,
+		name: &str,
+		mut parse: impl FnMut(&str) -> Result<T>,
+	) -> Result<Vec<T>> {
+		let section = self.next(name)?;
+		section
+			.lines
+			.iter()
+			.zip(section.first_line..)
+			.map(|(line, number)| parse(line).wrap_err(Line(number)))
+			.collect::<Result<_>>()
+			.wrap_err_with(|| in_section(name, &section))
+	}
+
+	/// Calls `parse` until all sections are used up, for inputs that are a list of blocks.
+	pub fn repeat<T>(&mut self, mut parse: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+		let mut result = Vec::new();
+		while !self.is_empty() {
+			result.push(parse(self)?);
+		}
+		Ok(result)
+	}
+
+	/// Makes sure that all sections have been read.
+	pub fn finish(self) -> Result<()> {
+		match self.sections.front() {
+			Some(section) => Err(eyre!(
+				"unexpected input starting at line {}",
+				section.first_line
+			)),
+			None => Ok(()),
+		}
+	}
+}
+
+fn in_section(name: &str, section: &Section) -> String {
+	format!("in the {name} starting at line {}", section.first_line)
+}
+
+/// Moves the line numbers in errors from parsing a block to where the block is in the input.
+fn shift_lines(mut report: Report, offset: usize) -> Report {
+	if let Some(Line(line)) = report.downcast_mut::<Line>() {
+		*line += offset;
+	}
+	if let Some(error) = report.downcast_mut::<ParseError>() {
+		error.line += offset;
+	}
+	if let Some(GridError::Width { row, .. } | GridError::Tile { row, .. }) =
+		report.downcast_mut::<GridError>()
+	{
+		*row += offset;
+	}
+	report
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::try_for_each_line;
+
+	const INPUT: &str = "\r\n1\r\n2\r\n\r\n\r\n3\r\nx\r\n\r\n4\r\n";
+
+	#[test]
+	fn sections_are_read_in_order() -> Result<()> {
+		let mut sections = Sections::new(INPUT);
+		let first = sections.lines("first", |line| Ok(line.parse::<u32>()?))?;
+		assert_eq!(first, [1, 2]);
+		let second = sections.block("second", |block| Ok(block.to_string()))?;
+		assert_eq!(second, "3\nx\n");
+		assert!(sections.finish().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn errors_name_the_section_and_line() {
+		let mut sections = Sections::new(INPUT);
+		let error = sections
+			.repeat(|sections| {
+				sections.block("number", |block| {
+					try_for_each_line(block, |line| Ok(line.trim().parse::<u32>().map(drop)?))
+				})
+			})
+			.unwrap_err();
+		assert_eq!(error.to_string(), "in the number starting at line 6");
+		assert_eq!(
+			error.root_cause().to_string(),
+			"invalid digit found in string"
+		);
+		let line = error.chain().nth(1).map(ToString::to_string);
+		assert_eq!(line.as_deref(), Some("line 7"));
+
+		let mut sections = Sections::new("1\n");
+		sections.lines("first", |_| Ok(())).unwrap();
+		let error = sections.block("second", |_| Ok(())).unwrap_err();
+		assert_eq!(error.to_string(), "missing the second");
+	}
+}
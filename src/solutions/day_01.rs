@@ -1,26 +1,82 @@
-use crate::read_segmented;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::Result;
-use linkme::distributed_slice;
-
-fn solution() -> Result<()> {
-	let entries = read_segmented::<u32, _>("inputs/day-01")?;
-
-	let mut sums: Vec<u32> = entries
-		.into_iter()
-		.map(|segment| segment.into_iter().sum())
-		.collect();
-
-	let (top_two, third, _) = sums
-		.as_mut_slice()
-		.select_nth_unstable_by(2, |a, b| b.cmp(a));
-
-	println!("{}", top_two.iter().max().expect("unreachable"));
-	println!("{}", top_two.iter().sum::<u32>() + *third);
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_01: (usize, fn() -> Result<()>) = (1, solution);
+use crate::generate::InputRng;
+use crate::read_segmented;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use rand::Rng;
+
+struct Day01;
+
+impl Puzzle for Day01 {
+	type Parsed = Vec<u64>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		let entries = read_segmented::<u32>(input)?;
+
+		Ok(entries
+			.into_iter()
+			// summed up as u64, so that no number of elves with u32 calories can overflow
+			.map(|segment| segment.into_iter().map(u64::from).sum())
+			.collect())
+	}
+
+	fn part_1(sums: &Self::Parsed) -> Result<Answer> {
+		let max = sums.iter().max().ok_or_else(|| eyre!("no elves"))?;
+		Ok((*max).into())
+	}
+
+	fn part_2(sums: &Self::Parsed) -> Result<Answer> {
+		if sums.len() < 3 {
+			return Err(eyre!("fewer than three elves"));
+		}
+
+		let mut sums = sums.clone();
+		let (top_two, third, _) = sums
+			.as_mut_slice()
+			.select_nth_unstable_by(2, |a, b| b.cmp(a));
+
+		Ok((top_two.iter().sum::<u64>() + *third).into())
+	}
+
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let elves: Vec<String> = (0..size.max(3))
+			.map(|_| {
+				(0..rng.gen_range(1..=15))
+					.map(|_| format!("{}\n", rng.gen_range(1000..=60000)))
+					.collect()
+			})
+			.collect();
+		Ok(elves.join("\n"))
+	}
+}
+
+solution!(Day01, day = 1, title = "Calorie Counting");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day01::solve(EXAMPLE)?, Answers::new(24000, 45000));
+		Ok(())
+	}
+}
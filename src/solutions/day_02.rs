@@ -1,112 +1,151 @@
-use crate::{try_for_each_line_in_file, SOLUTIONS};
-
-use color_eyre::eyre::Result;
-use linkme::distributed_slice;
-
-#[derive(Clone, Copy, Debug)]
-#[repr(i8)]
-enum RochambeauPlay {
-	Rock = 0,
-	Paper,
-	Scissors,
-}
-
-use RochambeauPlay::*;
-
-impl RochambeauPlay {
-	fn score(self) -> u64 {
-		self as u64 + 1
-	}
-
-	fn outcome(self, response: Self) -> RochambeauOutcome {
-		match (response as i8 - self as i8 + 4) % 3 - 1 {
-			-1 => Loss,
-			0 => Draw,
-			1 => Win,
-			_ => unreachable!(),
-		}
-	}
-}
-
-#[derive(Clone, Copy, Debug)]
-#[repr(i8)]
-enum RochambeauOutcome {
-	Loss = -1,
-	Draw,
-	Win,
-}
-
-use RochambeauOutcome::*;
-
-impl RochambeauOutcome {
-	fn score(self) -> u64 {
-		(self as i8 + 1) as u64 * 3
-	}
-
-	fn response(self, call: RochambeauPlay) -> RochambeauPlay {
-		match (self as i8 + call as i8 + 3) % 3 {
-			0 => Rock,
-			1 => Paper,
-			2 => Scissors,
-			_ => unreachable!(),
-		}
-	}
-}
-
-fn read_games() -> Result<Vec<(RochambeauPlay, RochambeauPlay)>> {
-	let mut result = Vec::new();
-	try_for_each_line_in_file("inputs/day-02", |line| {
-		if let Some((call, response)) = line.trim().split_once(' ') {
-			let call = match call {
-				"A" => Rock,
-				"B" => Paper,
-				"C" => Scissors,
-				_ => panic!("unexpected Rochambeau call {call}"),
-			};
-			let response = match response {
-				"X" => Rock,
-				"Y" => Paper,
-				"Z" => Scissors,
-				_ => panic!("unexpected Rochambeau response {response}"),
-			};
-			result.push((call, response));
-		}
-		Ok(())
-	})?;
-	Ok(result)
-}
-
-fn score(call: RochambeauPlay, response: RochambeauPlay) -> u64 {
-	response.score() + call.outcome(response).score()
-}
-
-fn score_outcome(call: RochambeauPlay, outcome: RochambeauOutcome) -> u64 {
-	outcome.response(call).score() + outcome.score()
-}
-
-fn solution() -> Result<()> {
-	let games = read_games()?;
-	let solution_1 = games
-		.iter()
-		.copied()
-		.map(|(call, response)| score(call, response))
-		.sum::<u64>();
-	println!("{solution_1}");
-
-	let solution_2 = games
-		.into_iter()
-		.map(|(call, response)| {
-			let outcome = match response {
-				Rock => Loss,
-				Paper => Draw,
-				Scissors => Win,
-			};
-			score_outcome(call, outcome)
-		})
-		.sum::<u64>();
-	println!("{solution_2}");
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_02: (usize, fn() -> Result<()>) = (2, solution);
+use crate::generate::InputRng;
+use crate::{solution, try_for_each_line, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug)]
+#[repr(i8)]
+enum RochambeauPlay {
+	Rock = 0,
+	Paper,
+	Scissors,
+}
+
+use RochambeauPlay::*;
+
+impl RochambeauPlay {
+	fn score(self) -> u64 {
+		self as u64 + 1
+	}
+
+	fn outcome(self, response: Self) -> RochambeauOutcome {
+		match (response as i8 - self as i8 + 4) % 3 - 1 {
+			-1 => Loss,
+			0 => Draw,
+			1 => Win,
+			_ => unreachable!(),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(i8)]
+enum RochambeauOutcome {
+	Loss = -1,
+	Draw,
+	Win,
+}
+
+use RochambeauOutcome::*;
+
+impl RochambeauOutcome {
+	fn score(self) -> u64 {
+		(self as i8 + 1) as u64 * 3
+	}
+
+	fn response(self, call: RochambeauPlay) -> RochambeauPlay {
+		match (self as i8 + call as i8 + 3) % 3 {
+			0 => Rock,
+			1 => Paper,
+			2 => Scissors,
+			_ => unreachable!(),
+		}
+	}
+}
+
+fn read_games(input: &str) -> Result<Vec<(RochambeauPlay, RochambeauPlay)>> {
+	let mut result = Vec::new();
+	try_for_each_line(input, |line| {
+		if let Some((call, response)) = line.trim().split_once(' ') {
+			let call = match call {
+				"A" => Rock,
+				"B" => Paper,
+				"C" => Scissors,
+				_ => return Err(eyre!("unexpected Rochambeau call {call}")),
+			};
+			let response = match response {
+				"X" => Rock,
+				"Y" => Paper,
+				"Z" => Scissors,
+				_ => return Err(eyre!("unexpected Rochambeau response {response}")),
+			};
+			result.push((call, response));
+		}
+		Ok(())
+	})?;
+	Ok(result)
+}
+
+fn score(call: RochambeauPlay, response: RochambeauPlay) -> u64 {
+	response.score() + call.outcome(response).score()
+}
+
+fn score_outcome(call: RochambeauPlay, outcome: RochambeauOutcome) -> u64 {
+	outcome.response(call).score() + outcome.score()
+}
+
+struct Day02;
+
+impl Puzzle for Day02 {
+	type Parsed = Vec<(RochambeauPlay, RochambeauPlay)>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_games(input)
+	}
+
+	fn part_1(games: &Self::Parsed) -> Result<Answer> {
+		let solution_1 = games
+			.iter()
+			.copied()
+			.map(|(call, response)| score(call, response))
+			.sum::<u64>();
+		Ok(solution_1.into())
+	}
+
+	fn part_2(games: &Self::Parsed) -> Result<Answer> {
+		let solution_2 = games
+			.iter()
+			.copied()
+			.map(|(call, response)| {
+				let outcome = match response {
+					Rock => Loss,
+					Paper => Draw,
+					Scissors => Win,
+				};
+				score_outcome(call, outcome)
+			})
+			.sum::<u64>();
+		Ok(solution_2.into())
+	}
+
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		Ok((0..size)
+			.map(|_| {
+				let call = rng.gen_range(b'A'..=b'C') as char;
+				let response = rng.gen_range(b'X'..=b'Z') as char;
+				format!("{call} {response}\n")
+			})
+			.collect())
+	}
+}
+
+solution!(Day02, day = 2, title = "Rock Paper Scissors");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+A Y
+B X
+C Z
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day02::solve(EXAMPLE)?, Answers::new(15, 12));
+		Ok(())
+	}
+}
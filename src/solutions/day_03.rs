@@ -1,58 +1,128 @@
-use crate::{try_for_each_line_in_file, SOLUTIONS};
-
-use color_eyre::eyre::{eyre, Result};
-use itertools::Itertools;
-use linkme::distributed_slice;
-
-fn solution() -> Result<()> {
-	let mut total_rucksacks = 0;
-	let mut total_groups = 0;
-	let mut elf = 0usize;
-	let mut group_set = u64::MAX;
-	try_for_each_line_in_file("inputs/day-03", |line| {
-		let rucksack = line.trim().as_bytes();
-		let size = rucksack.len();
-		if size % 2 != 0 {
-			return Err(eyre!("unexpected rucksack size"));
-		}
-
-		let (left, right) = rucksack.split_at(size / 2);
-		let [left, right] = [left, right].map(|pocket| {
-			pocket
-				.iter()
-				.map(|&c| match c {
-					b'a'..=b'z' => Ok(c - b'a'),
-					b'A'..=b'Z' => Ok(c - b'A' + 26),
-					_ => Err(eyre!("unexpected rucksack item")),
-				})
-				.fold_ok(0u64, |set, item| set | 1 << item)
-		});
-		// could avoid this with try_map (nightly only #![feature(array_try_map)] so far)
-		let (left, right) = (left?, right?);
-
-		let evaluate = |set: u64| -> u64 {
-			(0..52)
-				.map(|i| if set & (1 << i) != 0 { i + 1 } else { 0 })
-				.sum()
-		};
-
-		// part 1
-		total_rucksacks += evaluate(left & right);
-
-		// part 2
-		group_set &= left | right;
-		if elf % 3 == 2 {
-			total_groups += evaluate(group_set);
-			group_set = u64::MAX;
-		}
-
-		elf += 1;
-		Ok(())
-	})?;
-	println!("{total_rucksacks}");
-	println!("{total_groups}");
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_03: (usize, fn() -> Result<()>) = (3, solution);
+use crate::generate::InputRng;
+use crate::{solution, try_for_each_line, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+fn evaluate(set: u64) -> u64 {
+	(0..52)
+		.map(|i| if set & (1 << i) != 0 { i + 1 } else { 0 })
+		.sum()
+}
+
+struct Day03;
+
+impl Puzzle for Day03 {
+	type Parsed = Vec<[u64; 2]>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		let mut rucksacks = Vec::new();
+		try_for_each_line(input, |line| {
+			let rucksack = line.trim().as_bytes();
+			let size = rucksack.len();
+			if size % 2 != 0 {
+				return Err(eyre!("unexpected rucksack size"));
+			}
+
+			let (left, right) = rucksack.split_at(size / 2);
+			let [left, right] = [left, right].map(|pocket| {
+				pocket
+					.iter()
+					.map(|&c| match c {
+						b'a'..=b'z' => Ok(c - b'a'),
+						b'A'..=b'Z' => Ok(c - b'A' + 26),
+						_ => Err(eyre!("unexpected rucksack item")),
+					})
+					.fold_ok(0u64, |set, item| set | 1 << item)
+			});
+			// could avoid this with try_map (nightly only #![feature(array_try_map)] so far)
+			rucksacks.push([left?, right?]);
+			Ok(())
+		})?;
+		Ok(rucksacks)
+	}
+
+	fn part_1(rucksacks: &Self::Parsed) -> Result<Answer> {
+		let total_rucksacks = rucksacks
+			.iter()
+			.map(|[left, right]| evaluate(left & right))
+			.sum::<u64>();
+		Ok(total_rucksacks.into())
+	}
+
+	fn part_2(rucksacks: &Self::Parsed) -> Result<Answer> {
+		let total_groups = rucksacks
+			.chunks_exact(3)
+			.map(|group| {
+				let group_set = group
+					.iter()
+					.fold(u64::MAX, |group_set, [left, right]| group_set & (left | right));
+				evaluate(group_set)
+			})
+			.sum::<u64>();
+		Ok(total_groups.into())
+	}
+
+	/// Generates groups of three rucksacks, since part 2 expects exactly one badge per group.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let item = |priority: u8| match priority {
+			0..=25 => (b'a' + priority) as char,
+			_ => (b'A' + priority - 26) as char,
+		};
+		let mut rucksacks = String::new();
+		for _ in 0..size.div_ceil(3) {
+			let mut items: Vec<u8> = (0..52).collect();
+			items.shuffle(rng);
+			let (badge, others) = items.split_first().expect("there are 52 items");
+			for elf in 0..3 {
+				// every other item is missing from one of the rucksacks, so that only the badge is
+				// in all three
+				let mut allowed: Vec<u8> = others.iter().copied().skip(elf).step_by(3).collect();
+				allowed.extend(others.iter().skip((elf + 1) % 3).step_by(3));
+				allowed.shuffle(rng);
+				let shared = allowed.pop().expect("every rucksack allows some items");
+				// the compartments share no other item either
+				let (left, right) = allowed.split_at(allowed.len() / 2);
+				let half = rng.gen_range(2..=16);
+				let mut left: Vec<u8> = [*badge, shared]
+					.into_iter()
+					.chain((2..half).map(|_| *left.choose(rng).expect("not empty")))
+					.collect();
+				let mut right: Vec<u8> = [shared]
+					.into_iter()
+					.chain((1..half).map(|_| *right.choose(rng).expect("not empty")))
+					.collect();
+				left.shuffle(rng);
+				right.shuffle(rng);
+				rucksacks.extend(left.into_iter().chain(right).map(item));
+				rucksacks.push('\n');
+			}
+		}
+		Ok(rucksacks)
+	}
+}
+
+solution!(Day03, day = 3, title = "Rucksack Reorganization");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day03::solve(EXAMPLE)?, Answers::new(157, 70));
+		Ok(())
+	}
+}
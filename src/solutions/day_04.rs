@@ -1,40 +1,97 @@
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-
-fn solution() -> Result<()> {
-	let mut full_overlap_count = 0usize;
-	let mut any_overlap_count = 0usize;
-	try_for_each_line_in_file("inputs/day-04", |line| {
-		let line = line.trim();
-		let (l, r) = line
-			.split_once(',')
-			.ok_or_else(|| eyre!("invalid entry format {line}"))?;
-		let [l, r] = [l, r].map(|range| -> Result<_> {
-			let (s, e) = range
-				.split_once('-')
-				.ok_or_else(|| eyre!("invalid range format {range}"))?;
-			let [s, e] = [s, e].map(|i| i.parse::<usize>());
-			let (s, e) = (s?, e?);
-			Ok(s..=e)
-		});
-		let (l, r) = (l?, r?);
-		let full_overlap = l.contains(r.start()) && l.contains(r.end())
-			|| r.contains(l.start()) && r.contains(l.end());
-		full_overlap_count += full_overlap as usize;
-		let any_overlap = l.contains(r.start())
-			|| l.contains(r.end())
-			|| r.contains(l.start())
-			|| r.contains(l.end());
-		any_overlap_count += any_overlap as usize;
-		Ok(())
-	})?;
-	println!("{full_overlap_count}");
-	println!("{any_overlap_count}");
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_04: (usize, fn() -> Result<()>) = (4, solution);
+use std::ops::RangeInclusive;
+
+use crate::generate::InputRng;
+use crate::try_for_each_line;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use rand::Rng;
+
+type Assignment = RangeInclusive<usize>;
+
+struct Day04;
+
+impl Puzzle for Day04 {
+	type Parsed = Vec<(Assignment, Assignment)>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		let mut pairs = Vec::new();
+		try_for_each_line(input, |line| {
+			let line = line.trim();
+			let (l, r) = line
+				.split_once(',')
+				.ok_or_else(|| eyre!("invalid entry format {line}"))?;
+			let [l, r] = [l, r].map(|range| -> Result<_> {
+				let (s, e) = range
+					.split_once('-')
+					.ok_or_else(|| eyre!("invalid range format {range}"))?;
+				let [s, e] = [s, e].map(|i| i.parse::<usize>());
+				let (s, e) = (s?, e?);
+				Ok(s..=e)
+			});
+			pairs.push((l?, r?));
+			Ok(())
+		})?;
+		Ok(pairs)
+	}
+
+	fn part_1(pairs: &Self::Parsed) -> Result<Answer> {
+		let full_overlap_count = pairs
+			.iter()
+			.filter(|(l, r)| {
+				l.contains(r.start()) && l.contains(r.end())
+					|| r.contains(l.start()) && r.contains(l.end())
+			})
+			.count();
+		Ok(full_overlap_count.into())
+	}
+
+	fn part_2(pairs: &Self::Parsed) -> Result<Answer> {
+		let any_overlap_count = pairs
+			.iter()
+			.filter(|(l, r)| {
+				l.contains(r.start())
+					|| l.contains(r.end())
+					|| r.contains(l.start())
+					|| r.contains(l.end())
+			})
+			.count();
+		Ok(any_overlap_count.into())
+	}
+
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let mut assignment = || {
+			let start = rng.gen_range(1..=99);
+			(start, rng.gen_range(start..=99))
+		};
+		Ok((0..size)
+			.map(|_| {
+				let ((a, b), (c, d)) = (assignment(), assignment());
+				format!("{a}-{b},{c}-{d}\n")
+			})
+			.collect())
+	}
+}
+
+solution!(Day04, day = 4, title = "Camp Cleanup");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day04::solve(EXAMPLE)?, Answers::new(2, 4));
+		Ok(())
+	}
+}
@@ -1,127 +1,197 @@
-use std::cmp::Ordering::*;
-
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-
-struct Instruction {
-	count: usize,
-	from: usize,
-	to: usize,
-}
-
-fn strip_crlf(line: &str) -> &str {
-	match line.strip_suffix('\n') {
-		Some(line) => line.strip_suffix('\r').unwrap_or(line),
-		None => line,
-	}
-}
-
-fn read_input() -> Result<(Vec<Vec<u8>>, Vec<Instruction>)> {
-	let [p0, p1, p2, p3] = [
-		r"^(    |\[[A-Z]\] )*(   |\[[A-Z]\])$",
-		r"^( \d  )+( \d )$",
-		r"^move (\d+) from (\d+) to (\d+)$",
-		r"^$",
-	]
-	.map(regex::Regex::new);
-	let patterns = [p0?, p1?, p2?, p3?];
-
-	let mut stacks: Vec<Vec<u8>> = Vec::new();
-	let mut instructions = Vec::new();
-	try_for_each_line_in_file("inputs/day-05", |line| {
-		let line = strip_crlf(line);
-		for (index, pattern) in patterns.iter().enumerate() {
-			if let Some(captures) = pattern.captures(line) {
-				match index {
-					0 => {
-						if stacks.is_empty() {
-							stacks.resize_with(line.as_bytes().chunks(4).count(), Vec::new);
-						}
-						line.as_bytes().chunks(4).enumerate().for_each(|(i, c)| {
-							if c[0] == b'[' {
-								stacks[i].push(c[1]);
-							}
-						});
-					}
-					2 => {
-						instructions.push(Instruction {
-							count: captures.get(1).unwrap().as_str().parse::<usize>()?,
-							from: captures.get(2).unwrap().as_str().parse::<usize>()? - 1,
-							to: captures.get(3).unwrap().as_str().parse::<usize>()? - 1,
-						});
-					}
-					1 | 3 => {}
-					_ => unreachable!(),
-				}
-				return Ok(());
-			}
-		}
-
-		Err(eyre!("invalid input"))
-	})?;
-
-	stacks.iter_mut().for_each(|stack| stack.reverse());
-	Ok((stacks, instructions))
-}
-
-fn print_stack_tops(stacks: &Vec<Vec<u8>>) {
-	for stack in stacks {
-		if let Some(item) = stack.last() {
-			let item = *item as char;
-			print!("{item}")
-		} else {
-			print!(" ")
-		}
-	}
-	println!();
-}
-
-fn solution() -> Result<()> {
-	let (mut stacks, instructions) = read_input()?;
-	let stacks_cloned = stacks.clone();
-
-	for Instruction { count, from, to } in instructions.iter() {
-		(0..*count).try_for_each(|_| -> Result<()> {
-			let item = stacks[*from]
-				.pop()
-				.ok_or_else(|| eyre!("too many items removed from stack"))?;
-			stacks[*to].push(item);
-			Ok(())
-		})?;
-	}
-
-	print_stack_tops(&stacks);
-
-	let mut stacks = stacks_cloned;
-	for Instruction { count, from, to } in instructions.iter() {
-		let (from, to) = match from.cmp(to) {
-			Less => {
-				let (f, t) = stacks.split_at_mut(*to);
-				(&mut f[*from], &mut t[0])
-			}
-			Equal => {
-				continue;
-			}
-			Greater => {
-				let (t, f) = stacks.split_at_mut(*from);
-				(&mut f[0], &mut t[*to])
-			}
-		};
-
-		let e = from.len();
-		if e < *count {
-			return Err(eyre!("too many items removed from stack"));
-		}
-		to.extend(from.drain(e - *count..e));
-	}
-
-	print_stack_tops(&stacks);
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_05: (usize, fn() -> Result<()>) = (5, solution);
+use std::cmp::Ordering::*;
+
+use crate::generate::InputRng;
+use crate::sections::Sections;
+use crate::{capture, try_for_each_line};
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use once_cell::sync::Lazy;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::{Captures, Regex};
+
+// compiled once, since the fuzz targets parse a lot of inputs
+static CRATES: Lazy<Regex> =
+	Lazy::new(|| Regex::new(r"^(    |\[[A-Z]\] )*(   |\[[A-Z]\])$").expect("valid pattern"));
+static NUMBERS: Lazy<Regex> = Lazy::new(|| Regex::new(r"^( \d  )+( \d )$").expect("valid pattern"));
+static MOVE: Lazy<Regex> =
+	Lazy::new(|| Regex::new(r"^move (\d+) from (\d+) to (\d+)$").expect("valid pattern"));
+
+struct Instruction {
+	count: usize,
+	from: usize,
+	to: usize,
+}
+
+fn read_drawing(drawing: &str) -> Result<Vec<Vec<u8>>> {
+	let mut stacks: Vec<Vec<u8>> = Vec::new();
+	try_for_each_line(drawing, |line| {
+		let line = line.trim_end_matches('\n');
+		if NUMBERS.is_match(line) {
+			return Ok(());
+		}
+		if !CRATES.is_match(line) {
+			return Err(eyre!("invalid crates"));
+		}
+		let width = line.as_bytes().chunks(4).count();
+		if stacks.len() < width {
+			stacks.resize_with(width, Vec::new);
+		}
+		line.as_bytes().chunks(4).enumerate().for_each(|(i, c)| {
+			if c[0] == b'[' {
+				stacks[i].push(c[1]);
+			}
+		});
+		Ok(())
+	})?;
+
+	stacks.iter_mut().for_each(|stack| stack.reverse());
+	Ok(stacks)
+}
+
+fn read_input(input: &str) -> Result<(Vec<Vec<u8>>, Vec<Instruction>)> {
+	let mut sections = Sections::new(input);
+	let stacks = sections.block("drawing", read_drawing)?;
+	let stack = |captures: &Captures, group| -> Result<usize> {
+		let number = capture(captures, group)?.parse::<usize>()?;
+		(1..=stacks.len())
+			.contains(&number)
+			.then(|| number - 1)
+			.ok_or_else(|| eyre!("there is no stack {number}"))
+	};
+	let instructions = sections.lines("moves", |line| {
+		let captures = MOVE.captures(line).ok_or_else(|| eyre!("invalid move"))?;
+		Ok(Instruction {
+			count: capture(&captures, 1)?.parse::<usize>()?,
+			from: stack(&captures, 2)?,
+			to: stack(&captures, 3)?,
+		})
+	})?;
+	sections.finish()?;
+
+	Ok((stacks, instructions))
+}
+
+fn stack_tops(stacks: &[Vec<u8>]) -> String {
+	stacks
+		.iter()
+		.map(|stack| stack.last().map_or(' ', |item| *item as char))
+		.collect()
+}
+
+struct Day05;
+
+impl Puzzle for Day05 {
+	type Parsed = (Vec<Vec<u8>>, Vec<Instruction>);
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_input(input)
+	}
+
+	fn part_1((stacks, instructions): &Self::Parsed) -> Result<Answer> {
+		let mut stacks = stacks.clone();
+		for Instruction { count, from, to } in instructions.iter() {
+			(0..*count).try_for_each(|_| -> Result<()> {
+				let item = stacks[*from]
+					.pop()
+					.ok_or_else(|| eyre!("too many items removed from stack"))?;
+				stacks[*to].push(item);
+				Ok(())
+			})?;
+		}
+
+		Ok(stack_tops(&stacks).into())
+	}
+
+	fn part_2((stacks, instructions): &Self::Parsed) -> Result<Answer> {
+		let mut stacks = stacks.clone();
+		for Instruction { count, from, to } in instructions.iter() {
+			let (from, to) = match from.cmp(to) {
+				Less => {
+					let (f, t) = stacks.split_at_mut(*to);
+					(&mut f[*from], &mut t[0])
+				}
+				Equal => {
+					continue;
+				}
+				Greater => {
+					let (t, f) = stacks.split_at_mut(*from);
+					(&mut f[0], &mut t[*to])
+				}
+			};
+
+			let e = from.len();
+			if e < *count {
+				return Err(eyre!("too many items removed from stack"));
+			}
+			to.extend(from.drain(e - *count..e));
+		}
+
+		Ok(stack_tops(&stacks).into())
+	}
+
+	/// Generates `size` moves, which never take more crates from a stack than it holds.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let mut stacks: Vec<Vec<u8>> = (0..rng.gen_range(2..=9))
+			.map(|_| {
+				(0..rng.gen_range(1..=8))
+					.map(|_| rng.gen_range(b'A'..=b'Z'))
+					.collect()
+			})
+			.collect();
+		let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+		let mut input = String::new();
+		for level in (0..height).rev() {
+			let crates = stacks.iter().map(|stack| match stack.get(level) {
+				Some(&item) => format!("[{}]", item as char),
+				None => "   ".to_string(),
+			});
+			input += &crates.collect::<Vec<_>>().join(" ");
+			input.push('\n');
+		}
+		let numbers = (1..=stacks.len()).map(|number| format!(" {number} "));
+		input += &numbers.collect::<Vec<_>>().join(" ");
+		input += "\n\n";
+
+		for _ in 0..size {
+			let filled: Vec<_> = (0..stacks.len())
+				.filter(|&from| !stacks[from].is_empty())
+				.collect();
+			let from = *filled.choose(rng).expect("the crates are somewhere");
+			let to = (from + rng.gen_range(1..stacks.len())) % stacks.len();
+			let count = rng.gen_range(1..=stacks[from].len());
+			let rest = stacks[from].len() - count;
+			let moved = stacks[from].split_off(rest);
+			stacks[to].extend(moved);
+			input += &format!("move {count} from {} to {}\n", from + 1, to + 1);
+		}
+		Ok(input)
+	}
+}
+
+solution!(Day05, day = 5, title = "Supply Stacks");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = concat!(
+		"    [D]    \n",
+		"[N] [C]    \n",
+		"[Z] [M] [P]\n",
+		" 1   2   3 \n",
+		"\n",
+		"move 1 from 2 to 1\n",
+		"move 3 from 1 to 3\n",
+		"move 2 from 2 to 1\n",
+		"move 1 from 1 to 2\n",
+	);
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day05::solve(EXAMPLE)?, Answers::new("CMZ", "MCD"));
+		Ok(())
+	}
+}
@@ -1,29 +1,84 @@
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use itertools::Itertools;
-use linkme::distributed_slice;
-
-fn find_marker(data: &[u8], size: usize) -> Option<usize> {
-	let mut temp_storage = Vec::with_capacity(size);
-	data.windows(size)
-		.find_position(|&w| {
-			temp_storage.clear();
-			temp_storage.extend_from_slice(w);
-			temp_storage.sort();
-			temp_storage.windows(2).all(|w| w[0] != w[1])
-		})
-		.map(|(wi, _)| wi + size)
-}
-
-fn solution() -> Result<()> {
-	let data = include_bytes!("../../inputs/day-06");
-	for size in [4, 14] {
-		let solution = find_marker(data, size).ok_or_else(|| eyre!("no marker found"))?;
-		println!("{solution}");
-	}
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_06: (usize, fn() -> Result<()>) = (6, solution);
+use crate::generate::InputRng;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+fn find_marker(data: &[u8], size: usize) -> Option<usize> {
+	let mut temp_storage = Vec::with_capacity(size);
+	data.windows(size)
+		.find_position(|&w| {
+			temp_storage.clear();
+			temp_storage.extend_from_slice(w);
+			temp_storage.sort();
+			temp_storage.windows(2).all(|w| w[0] != w[1])
+		})
+		.map(|(wi, _)| wi + size)
+}
+
+struct Day06;
+
+impl Puzzle for Day06 {
+	type Parsed = Vec<u8>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		Ok(input.trim().as_bytes().to_vec())
+	}
+
+	fn part_1(data: &Self::Parsed) -> Result<Answer> {
+		let marker = find_marker(data, 4).ok_or_else(|| eyre!("no marker found"))?;
+		Ok(marker.into())
+	}
+
+	fn part_2(data: &Self::Parsed) -> Result<Answer> {
+		let marker = find_marker(data, 14).ok_or_else(|| eyre!("no marker found"))?;
+		Ok(marker.into())
+	}
+
+	/// Generates a stream of `size` characters with both markers in it.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let size = size.max(14);
+		let mut letters: Vec<u8> = (b'a'..=b'z').collect();
+		letters.shuffle(rng);
+		// three letters can't make a marker, so the first one starts where the 14 distinct letters do
+		let mut stream: Vec<u8> = (0..rng.gen_range(0..=size - 14))
+			.map(|_| *letters[..3].choose(rng).expect("not empty"))
+			.collect();
+		letters.shuffle(rng);
+		stream.extend(&letters[..14]);
+		stream.extend((stream.len()..size).map(|_| rng.gen_range(b'a'..=b'z')));
+		stream.push(b'\n');
+		Ok(String::from_utf8(stream)?)
+	}
+}
+
+solution!(Day06, day = 6, title = "Tuning Trouble");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day06::solve(EXAMPLE)?, Answers::new(7, 19));
+		Ok(())
+	}
+
+	#[test]
+	fn more_examples() -> Result<()> {
+		for (input, part_1, part_2) in [
+			("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+			("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+			("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+			("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+		] {
+			assert_eq!(Day06::solve(input)?, Answers::new(part_1, part_2));
+		}
+		Ok(())
+	}
+}
@@ -1,141 +1,271 @@
-use std::collections::hash_map::Entry::*;
-use std::collections::HashMap;
-
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-
-#[derive(Debug, Clone)]
-enum Node {
-	File(usize),
-	Directory(HashMap<String, Node>),
-}
-
-use Node::*;
-
-impl Node {
-	fn size(&self) -> usize {
-		match self {
-			File(s) => *s,
-			Directory(ns) => ns.values().map(Node::size).sum(),
-		}
-	}
-}
-
-fn part_1(node: &Node) -> usize {
-	if let Directory(entries) = node {
-		let size = node.size();
-		(if size <= 100_000 { size } else { 0 })
-			+ entries.iter().map(|(_, node)| part_1(node)).sum::<usize>()
-	} else {
-		0
-	}
-}
-
-fn part_2(node: &Node) -> usize {
-	let total_size = node.size();
-	let total_available = 70_000_000;
-	let required = 30_000_000;
-	let to_free = total_size + required - total_available;
-	let mut smallest_sufficient = total_size;
-
-	fn recursion(to_free: usize, smallest_sufficient: &mut usize, node: &Node) {
-		if let Directory(entries) = node {
-			let size = node.size();
-			if size >= to_free && size < *smallest_sufficient {
-				*smallest_sufficient = size;
-			}
-			entries
-				.values()
-				.for_each(|e| recursion(to_free, smallest_sufficient, e));
-		}
-	}
-	recursion(to_free, &mut smallest_sufficient, node);
-
-	smallest_sufficient
-}
-
-fn solution() -> Result<()> {
-	let mut cwd: Option<Vec<String>> = None;
-	let mut in_ls = false;
-	let mut root = Directory(HashMap::new());
-	try_for_each_line_in_file("inputs/day-07", |line| {
-		let line = line.trim();
-		if let Some(parameters) = line.strip_prefix("$ cd ") {
-			in_ls = false;
-			match parameters {
-				"/" => {
-					cwd = Some(Vec::new());
-				}
-				".." => {
-					cwd.as_mut()
-						.ok_or_else(|| eyre!("can't leave an unknown directory"))?
-						.pop()
-						.ok_or_else(|| eyre!("can't leave the root directory"))?;
-				}
-				path => {
-					cwd.as_mut()
-						.ok_or_else(|| eyre!("can't access the children of an unknown directory"))?
-						.push(path.to_string());
-				}
-			}
-		} else if line == "$ ls" {
-			in_ls = true;
-			cwd.as_ref()
-				.ok_or_else(|| eyre!("can't list an unknown directory"))?;
-		} else {
-			if !in_ls {
-				return Err(eyre!("unexpected output in log file"));
-			}
-
-			let mut cur = match &mut root {
-				Directory(root) => root,
-				_ => unreachable!(),
-			};
-			for dir in cwd.as_ref().expect("unreachable") {
-				cur = match cur
-					.get_mut(dir)
-					.ok_or_else(|| eyre!("listing a non-existent directory"))?
-				{
-					Directory(target) => target,
-					_ => return Err(eyre!("listing a file instead of a directory")),
-				};
-			}
-
-			let (front, back) = line
-				.split_once(' ')
-				.ok_or_else(|| eyre!("missing separator in ls output"))?;
-			if front == "dir" {
-				cur.entry(back.to_string())
-					.or_insert_with(|| Directory(HashMap::new()));
-			} else {
-				let size = front.parse::<usize>()?;
-				match cur.entry(back.to_string()) {
-					Occupied(e) => {
-						if let File(size_existing) = e.get() {
-							if *size_existing != size {
-								return Err(eyre!("file size changed!"));
-							}
-						} else {
-							return Err(eyre!("directory is now a file!"));
-						}
-					}
-					Vacant(e) => {
-						e.insert(File(size));
-					}
-				}
-			}
-		}
-		Ok(())
-	})?;
-
-	println!("{}", part_1(&root));
-	println!("{}", part_2(&root));
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_07: (usize, fn() -> Result<()>) = (7, solution);
+use std::collections::hash_map::Entry::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::generate::InputRng;
+use crate::try_for_each_line;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[derive(Debug, Clone)]
+enum Node {
+	File(usize),
+	Directory(HashMap<String, Node>),
+}
+
+use Node::*;
+
+impl Node {
+	fn size(&self) -> usize {
+		match self {
+			File(s) => *s,
+			Directory(ns) => ns.values().map(Node::size).sum(),
+		}
+	}
+}
+
+fn part_1(node: &Node) -> usize {
+	if let Directory(entries) = node {
+		let size = node.size();
+		(if size <= 100_000 { size } else { 0 })
+			+ entries.values().map(part_1).sum::<usize>()
+	} else {
+		0
+	}
+}
+
+const TOTAL_AVAILABLE: usize = 70_000_000;
+const REQUIRED: usize = 30_000_000;
+
+fn part_2(node: &Node, total_available: usize, required: usize) -> usize {
+	let total_size = node.size();
+	let to_free = (total_size + required).saturating_sub(total_available);
+	let mut smallest_sufficient = total_size;
+
+	fn recursion(to_free: usize, smallest_sufficient: &mut usize, node: &Node) {
+		if let Directory(entries) = node {
+			let size = node.size();
+			if size >= to_free && size < *smallest_sufficient {
+				*smallest_sufficient = size;
+			}
+			entries
+				.values()
+				.for_each(|e| recursion(to_free, smallest_sufficient, e));
+		}
+	}
+	recursion(to_free, &mut smallest_sufficient, node);
+
+	smallest_sufficient
+}
+
+fn read_tree(input: &str) -> Result<Node> {
+	let mut cwd: Option<Vec<String>> = None;
+	let mut in_ls = false;
+	let mut root = Directory(HashMap::new());
+	try_for_each_line(input, |line| {
+		let line = line.trim();
+		if let Some(parameters) = line.strip_prefix("$ cd ") {
+			in_ls = false;
+			match parameters {
+				"/" => {
+					cwd = Some(Vec::new());
+				}
+				".." => {
+					cwd.as_mut()
+						.ok_or_else(|| eyre!("can't leave an unknown directory"))?
+						.pop()
+						.ok_or_else(|| eyre!("can't leave the root directory"))?;
+				}
+				path => {
+					cwd.as_mut()
+						.ok_or_else(|| eyre!("can't access the children of an unknown directory"))?
+						.push(path.to_string());
+				}
+			}
+		} else if line == "$ ls" {
+			in_ls = true;
+			cwd.as_ref()
+				.ok_or_else(|| eyre!("can't list an unknown directory"))?;
+		} else {
+			if !in_ls {
+				return Err(eyre!("unexpected output in log file"));
+			}
+
+			let mut cur = match &mut root {
+				Directory(root) => root,
+				_ => unreachable!(),
+			};
+			for dir in cwd.as_ref().expect("unreachable") {
+				cur = match cur
+					.get_mut(dir)
+					.ok_or_else(|| eyre!("listing a non-existent directory"))?
+				{
+					Directory(target) => target,
+					_ => return Err(eyre!("listing a file instead of a directory")),
+				};
+			}
+
+			let (front, back) = line
+				.split_once(' ')
+				.ok_or_else(|| eyre!("missing separator in ls output"))?;
+			if front == "dir" {
+				cur.entry(back.to_string())
+					.or_insert_with(|| Directory(HashMap::new()));
+			} else {
+				let size = front.parse::<usize>()?;
+				match cur.entry(back.to_string()) {
+					Occupied(e) => {
+						if let File(size_existing) = e.get() {
+							if *size_existing != size {
+								return Err(eyre!("file size changed!"));
+							}
+						} else {
+							return Err(eyre!("directory is now a file!"));
+						}
+					}
+					Vacant(e) => {
+						e.insert(File(size));
+					}
+				}
+			}
+		}
+		Ok(())
+	})?;
+
+	Ok(root)
+}
+
+struct Day07;
+
+impl Puzzle for Day07 {
+	type Parsed = Node;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_tree(input)
+	}
+
+	fn part_1(root: &Self::Parsed) -> Result<Answer> {
+		Ok(part_1(root).into())
+	}
+
+	fn part_2(root: &Self::Parsed) -> Result<Answer> {
+		Ok(part_2(root, TOTAL_AVAILABLE, REQUIRED).into())
+	}
+
+	/// Generates a log exploring `size` directories depth first like the actual inputs. Every
+	/// directory has a few small files, and large ones in the older half of them fill the disk up
+	/// to 41 to 65 MB, so part 2 has to free up some space.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		#[derive(Default)]
+		struct GeneratedDirectory {
+			entries: Vec<String>,
+			children: Vec<(String, usize)>,
+		}
+
+		let mut names = HashSet::new();
+		let mut name = |rng: &mut InputRng, parent: usize, extension: bool| loop {
+			let mut name: String = (0..rng.gen_range(1..=8))
+				.map(|_| rng.gen_range('a'..='z'))
+				.collect();
+			if extension {
+				name += [".txt", ".dat", ".log", ""].choose(rng).expect("not empty");
+			}
+			if names.insert((parent, name.clone())) {
+				return name;
+			}
+		};
+
+		let mut directories = vec![GeneratedDirectory::default()];
+		for directory in 1..size.max(1) {
+			let parent = rng.gen_range(0..directory);
+			let child = name(rng, parent, false);
+			directories[parent].entries.push(format!("dir {child}"));
+			directories[parent].children.push((child, directory));
+			directories.push(GeneratedDirectory::default());
+		}
+		let mut files = Vec::new();
+		for directory in 0..directories.len() {
+			for _ in 0..rng.gen_range(1..=4) {
+				files.push((directory, rng.gen_range(1000..=20_000)));
+			}
+		}
+		// parents come before their children, so the newer half of the directories (which has the
+		// last one, always without subdirectories) stays small enough for part 1
+		let mut total: usize = files.iter().map(|(_, bytes)| bytes).sum();
+		let target = rng.gen_range(41_000_000..=65_000_000);
+		while total < target {
+			let bytes = rng.gen_range(1_000_000..=5_000_000).min(target - total);
+			files.push((rng.gen_range(0..directories.len().div_ceil(2)), bytes));
+			total += bytes;
+		}
+		for (directory, bytes) in files {
+			let file = name(rng, directory, true);
+			directories[directory]
+				.entries
+				.push(format!("{bytes} {file}"));
+		}
+		for directory in &mut directories {
+			directory.entries.shuffle(rng);
+		}
+
+		fn explore(directories: &[GeneratedDirectory], directory: usize, log: &mut String) {
+			*log += "$ ls\n";
+			for entry in &directories[directory].entries {
+				*log += entry;
+				log.push('\n');
+			}
+			for (name, child) in &directories[directory].children {
+				*log += &format!("$ cd {name}\n");
+				explore(directories, *child, log);
+				*log += "$ cd ..\n";
+			}
+		}
+		let mut log = "$ cd /\n".to_string();
+		explore(&directories, 0, &mut log);
+		Ok(log)
+	}
+}
+
+solution!(Day07, day = 7, title = "No Space Left On Device");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		let root = read_tree(EXAMPLE)?;
+		assert_eq!(part_1(&root), 95437);
+		assert_eq!(part_2(&root, 70_000_000, 30_000_000), 24933642);
+		assert_eq!(part_2(&root, 50_000_000, 30_000_000), 48381165);
+		Ok(())
+	}
+}
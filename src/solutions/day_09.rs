@@ -1,58 +1,131 @@
-use std::collections::HashSet;
-
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-
-fn solve<const K: usize>() -> Result<()> {
-	let mut knots = [[0i64; 2]; K];
-	let mut tail_positions = HashSet::<_>::from_iter([[0i64; 2]]);
-
-	try_for_each_line_in_file("inputs/day-09", |line| {
-		let line = line.trim();
-		let (direction, steps) = line
-			.split_once(' ')
-			.ok_or_else(|| eyre!("invalid entry format {line}"))?;
-		let steps = steps.parse::<i64>()?;
-
-		let (sign, axis) = match direction {
-			"R" => (1, 0),
-			"L" => (-1, 0),
-			"U" => (1, 1),
-			"D" => (-1, 1),
-			_ => return Err(eyre!("invalid entry format {line}")),
-		};
-
-		for _ in 0..steps {
-			knots[0][axis] += sign;
-			for k in 1..K {
-				let delta = [0, 1].map(|i| knots[k - 1][i] - knots[k][i]);
-				if delta.iter().map(|d| d.abs()).max().expect("unreachable") <= 1 {
-					break;
-				}
-				let update = delta.map(|d| d.signum());
-				knots[k] = [0, 1].map(|i| knots[k][i] + update[i]);
-				if k == K - 1 {
-					tail_positions.insert(knots[k]);
-				}
-			}
-		}
-
-		Ok(())
-	})?;
-
-	let visited = tail_positions.len();
-	println!("{visited}");
-
-	Ok(())
-}
-
-fn solution() -> Result<()> {
-	solve::<2>()?;
-	solve::<10>()
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_09: (usize, fn() -> Result<()>) = (9, solution);
+use std::collections::HashSet;
+
+use crate::generate::InputRng;
+use crate::geometry::{Dir4, Point};
+use crate::try_for_each_line;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug)]
+struct Motion {
+	direction: Dir4,
+	steps: usize,
+}
+
+fn read_motions(input: &str) -> Result<Vec<Motion>> {
+	let mut motions = Vec::new();
+	try_for_each_line(input, |line| {
+		let line = line.trim();
+		let (direction, steps) = line
+			.split_once(' ')
+			.ok_or_else(|| eyre!("invalid entry format {line}"))?;
+		let steps = steps.parse::<usize>()?;
+
+		let direction = match direction {
+			"R" => Dir4::Right,
+			"L" => Dir4::Left,
+			"U" => Dir4::Up,
+			"D" => Dir4::Down,
+			_ => return Err(eyre!("invalid entry format {line}")),
+		};
+
+		motions.push(Motion { direction, steps });
+		Ok(())
+	})?;
+	Ok(motions)
+}
+
+fn simulate<const K: usize>(motions: &[Motion]) -> usize {
+	let mut knots = [Point::<2>::ORIGIN; K];
+	let mut tail_positions = HashSet::<_>::from_iter([Point::ORIGIN]);
+
+	for &Motion { direction, steps } in motions {
+		for _ in 0..steps {
+			knots[0] += direction.offset();
+			for k in 1..K {
+				if knots[k].chebyshev(knots[k - 1]) <= 1 {
+					break;
+				}
+				knots[k] += (knots[k - 1] - knots[k]).signum();
+				if k == K - 1 {
+					tail_positions.insert(knots[k]);
+				}
+			}
+		}
+	}
+
+	tail_positions.len()
+}
+
+struct Day09;
+
+impl Puzzle for Day09 {
+	type Parsed = Vec<Motion>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_motions(input)
+	}
+
+	fn part_1(motions: &Self::Parsed) -> Result<Answer> {
+		Ok(simulate::<2>(motions).into())
+	}
+
+	fn part_2(motions: &Self::Parsed) -> Result<Answer> {
+		Ok(simulate::<10>(motions).into())
+	}
+
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		Ok((0..size)
+			.map(|_| {
+				let direction = ["R", "L", "U", "D"].choose(rng).expect("not empty");
+				format!("{direction} {}\n", rng.gen_range(1..=20))
+			})
+			.collect())
+	}
+}
+
+solution!(Day09, day = 9, title = "Rope Bridge");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+	const LARGER_EXAMPLE: &str = "\
+R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day09::solve(EXAMPLE)?, Answers::new(13, 1));
+		Ok(())
+	}
+
+	#[test]
+	fn larger_example() -> Result<()> {
+		let motions = read_motions(LARGER_EXAMPLE)?;
+		assert_eq!(simulate::<10>(&motions), 36);
+		Ok(())
+	}
+}
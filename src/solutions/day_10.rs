@@ -1,48 +1,274 @@
-use std::str::from_utf8;
-
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-
-fn solution() -> Result<()> {
-	let mut cycle = 0i32;
-	let mut x = 1;
-	let mut signal_strength = 0;
-	let mut pixels = [b'.'; 6 * 40];
-	let mut update = |add_x: Option<i32>| {
-		if (x - cycle % 40).abs() <= 1 {
-			pixels[cycle as usize % pixels.len()] = b'#';
-		}
-		cycle += 1;
-		if (cycle + 20) % 40 == 0 {
-			signal_strength += cycle * x;
-		}
-		if let Some(add_x) = add_x {
-			x += add_x;
-		}
-	};
-	try_for_each_line_in_file("inputs/day-10", |line| {
-		let line = line.trim();
-		if let Some(d) = line.strip_prefix("addx ") {
-			let d = d.parse::<i32>()?;
-			update(None);
-			update(Some(d));
-		} else if line == "noop" {
-			update(None);
-		} else {
-			return Err(eyre!("unknown instruction {line}"));
-		}
-		Ok(())
-	})?;
-	println!("{signal_strength}");
-	for scan_line in pixels.chunks(40) {
-		let scan_line = from_utf8(scan_line).expect("unreachable");
-		println!("{scan_line}");
-	}
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_10: (usize, fn() -> Result<()>) = (10, solution);
+use std::str::from_utf8;
+
+use crate::generate::InputRng;
+use crate::try_for_each_line;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug)]
+enum Instruction {
+	Noop,
+	AddX(i32),
+}
+
+fn read_program(input: &str) -> Result<Vec<Instruction>> {
+	let mut program = Vec::new();
+	try_for_each_line(input, |line| {
+		let line = line.trim();
+		if let Some(d) = line.strip_prefix("addx ") {
+			program.push(Instruction::AddX(d.parse::<i32>()?));
+		} else if line == "noop" {
+			program.push(Instruction::Noop);
+		} else {
+			return Err(eyre!("unknown instruction {line}"));
+		}
+		Ok(())
+	})?;
+	Ok(program)
+}
+
+fn run(program: &[Instruction]) -> (i32, [u8; 6 * 40]) {
+	let mut cycle = 0i32;
+	let mut x = 1;
+	let mut signal_strength = 0;
+	let mut pixels = [b'.'; 6 * 40];
+	let mut update = |add_x: Option<i32>| {
+		if (x - cycle % 40).abs() <= 1 {
+			pixels[cycle as usize % pixels.len()] = b'#';
+		}
+		cycle += 1;
+		if (cycle + 20) % 40 == 0 {
+			signal_strength += cycle * x;
+		}
+		if let Some(add_x) = add_x {
+			x += add_x;
+		}
+	};
+	for instruction in program {
+		match *instruction {
+			Instruction::AddX(d) => {
+				update(None);
+				update(Some(d));
+			}
+			Instruction::Noop => update(None),
+		}
+	}
+	(signal_strength, pixels)
+}
+
+struct Day10;
+
+impl Puzzle for Day10 {
+	type Parsed = Vec<Instruction>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_program(input)
+	}
+
+	fn part_1(program: &Self::Parsed) -> Result<Answer> {
+		let (signal_strength, _) = run(program);
+		Ok(signal_strength.into())
+	}
+
+	fn part_2(program: &Self::Parsed) -> Result<Answer> {
+		let (_, pixels) = run(program);
+		let image = pixels
+			.chunks(40)
+			.map(|scan_line| from_utf8(scan_line).expect("unreachable"))
+			.join("\n");
+		Ok(image.into())
+	}
+
+	/// Generates at least `size` instructions, and enough of them to draw the whole screen.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let (mut program, mut instructions, mut cycles, mut x) = (String::new(), 0, 0, 1);
+		while instructions < size || cycles < 240 {
+			if rng.gen_ratio(1, 3) {
+				program += "noop\n";
+				cycles += 1;
+			} else {
+				// keeps the sprite on the screen, mostly
+				let add_x = rng.gen_range(-1 - x..=40 - x).clamp(-10, 10);
+				program += &format!("addx {add_x}\n");
+				cycles += 2;
+				x += add_x;
+			}
+			instructions += 1;
+		}
+		Ok(program)
+	}
+}
+
+solution!(Day10, day = 10, title = "Cathode-Ray Tube");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+";
+
+	const IMAGE: &str = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day10::solve(EXAMPLE)?, Answers::new(13140, IMAGE));
+		Ok(())
+	}
+}
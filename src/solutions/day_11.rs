@@ -1,238 +1,353 @@
-use std::str::{from_utf8, FromStr};
-
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use itertools::Itertools;
-use linkme::distributed_slice;
-use nom::{
-	branch::alt,
-	bytes::complete::tag,
-	character::complete::{digit1, line_ending},
-	combinator::{map, map_res, opt},
-	multi::separated_list1,
-	sequence::{preceded, terminated, tuple},
-	IResult,
-};
-use num::Integer;
-
-#[derive(Clone, Copy, Debug)]
-enum Operation {
-	Square,
-	MulConst(u64),
-	AddConst(u64),
-}
-
-use Operation::*;
-
-impl Operation {
-	pub fn evaluate(self, old: u64) -> u64 {
-		match self {
-			Square => old * old,
-			MulConst(c) => old * c,
-			AddConst(c) => old + c,
-		}
-	}
-}
-
-#[derive(Clone, Debug)]
-struct Monkey {
-	id: usize,
-	items: Vec<u64>,
-	operation: Operation,
-	test_modulo: u64,
-	target_ids: [usize; 2],
-	items_inspected: usize,
-}
-
-fn parse_as_str<F: FromStr>(i: &[u8]) -> std::result::Result<F, ()> {
-	from_utf8(i).map_err(|_| ())?.parse().map_err(|_| ())
-}
-
-fn parse_monkey_id(i: &[u8]) -> IResult<&[u8], usize, ()> {
-	preceded(
-		tag(b"Monkey "),
-		terminated(
-			map_res(digit1, parse_as_str),
-			tuple((tag(b":"), line_ending)),
-		),
-	)(i)
-}
-
-fn parse_monkey_items(i: &[u8]) -> IResult<&[u8], Vec<u64>, ()> {
-	preceded(
-		tag(b"  Starting items: "),
-		terminated(
-			separated_list1(tag(b", "), map_res(digit1, parse_as_str)),
-			line_ending,
-		),
-	)(i)
-}
-
-fn parse_operation(i: &[u8]) -> IResult<&[u8], Operation, ()> {
-	preceded(
-		tag(b"  Operation: new = old "),
-		terminated(
-			alt((
-				map(tag(b"* old"), |_| Square),
-				map(
-					preceded(tag(b"* "), map_res(digit1, parse_as_str)),
-					MulConst,
-				),
-				map(
-					preceded(tag(b"+ "), map_res(digit1, parse_as_str)),
-					AddConst,
-				),
-			)),
-			line_ending,
-		),
-	)(i)
-}
-
-fn parse_test(i: &[u8]) -> IResult<&[u8], u64, ()> {
-	preceded(
-		tag(b"  Test: divisible by "),
-		terminated(map_res(digit1, parse_as_str), line_ending),
-	)(i)
-}
-
-fn parse_targets(i: &[u8]) -> IResult<&[u8], [usize; 2], ()> {
-	map(
-		tuple((
-			preceded(
-				tag(b"    If true: throw to monkey "),
-				terminated(map_res(digit1, parse_as_str), line_ending),
-			),
-			preceded(
-				tag(b"    If false: throw to monkey "),
-				terminated(map_res(digit1, parse_as_str), line_ending),
-			),
-		)),
-		|(t, f)| [t, f],
-	)(i)
-}
-
-fn parse_monkey(i: &[u8]) -> IResult<&[u8], Monkey, ()> {
-	map(
-		terminated(
-			tuple((
-				parse_monkey_id,
-				parse_monkey_items,
-				parse_operation,
-				parse_test,
-				parse_targets,
-			)),
-			opt(line_ending),
-		),
-		|(id, items, operation, test_modulo, target_ids)| Monkey {
-			id,
-			items,
-			operation,
-			test_modulo,
-			target_ids,
-			items_inspected: 0,
-		},
-	)(i)
-}
-
-fn parse_monkeys() -> Result<Vec<Monkey>> {
-	let input = std::fs::read_to_string("inputs/day-11")?;
-	let mut input = input.as_bytes();
-	let mut monkeys = Vec::new();
-	while let Ok((remainder, monkey)) = parse_monkey(input) {
-		if monkey.id != monkeys.len() {
-			return Err(eyre!("expected sorted monkeys"));
-		}
-		monkeys.push(monkey);
-		input = remainder;
-	}
-	if !input.is_empty() {
-		return Err(eyre!("input could not be parsed completely"));
-	}
-	if monkeys.iter().enumerate().any(|(i, m)| {
-		m.target_ids[0] == m.target_ids[1]
-			|| m.target_ids.iter().any(|&j| j >= monkeys.len() || i == j)
-	}) {
-		return Err(eyre!("invalid target monkeys"));
-	}
-	Ok(monkeys)
-}
-
-fn pick_disjoint_mut<T, const N: usize>(items: &mut [T], indices: [usize; N]) -> [&mut T; N] {
-	assert!(indices.iter().all(|&i| i < items.len()));
-	assert!(indices.iter().tuple_combinations().all(|(&i, &j)| i != j));
-	indices.map(|i| unsafe { &mut *items.as_mut_ptr().add(i) })
-}
-
-fn perform_round_1(monkeys: &mut [Monkey]) {
-	for i in 0..monkeys.len() {
-		let [monkey, true_monkey, false_monkey] = pick_disjoint_mut(
-			monkeys,
-			[i, monkeys[i].target_ids[0], monkeys[i].target_ids[1]],
-		);
-
-		monkey.items_inspected += monkey.items.len();
-		for item in monkey.items.drain(..) {
-			let item = monkey.operation.evaluate(item) / 3;
-			if item % monkey.test_modulo == 0 {
-				true_monkey.items.push(item);
-			} else {
-				false_monkey.items.push(item);
-			}
-		}
-	}
-}
-
-fn part_1(mut monkeys: Vec<Monkey>) {
-	(0..20).for_each(|_| perform_round_1(&mut monkeys));
-	monkeys.select_nth_unstable_by(1, |a, b| b.items_inspected.cmp(&a.items_inspected));
-	let score = monkeys[..2]
-		.iter()
-		.map(|m| m.items_inspected)
-		.product::<usize>();
-	println!("{score}");
-}
-
-fn perform_round_2(monkeys: &mut [Monkey], modulo: u64) {
-	for i in 0..monkeys.len() {
-		let [monkey, true_monkey, false_monkey] = pick_disjoint_mut(
-			monkeys,
-			[i, monkeys[i].target_ids[0], monkeys[i].target_ids[1]],
-		);
-
-		monkey.items_inspected += monkey.items.len();
-		for item in monkey.items.drain(..) {
-			let item = monkey.operation.evaluate(item) % modulo;
-			if item % monkey.test_modulo == 0 {
-				true_monkey.items.push(item);
-			} else {
-				false_monkey.items.push(item);
-			}
-		}
-	}
-}
-
-fn part_2(mut monkeys: Vec<Monkey>) {
-	let modulo = monkeys
-		.iter()
-		.map(|m| m.test_modulo)
-		.reduce(|c, d| c.lcm(&d))
-		.unwrap_or(1);
-	(0..10000).for_each(|_| perform_round_2(&mut monkeys, modulo));
-	monkeys.select_nth_unstable_by(1, |a, b| b.items_inspected.cmp(&a.items_inspected));
-	let score = monkeys[..2]
-		.iter()
-		.map(|m| m.items_inspected)
-		.product::<usize>();
-	println!("{score}");
-}
-
-fn solution() -> Result<()> {
-	let monkeys = parse_monkeys()?;
-	part_1(monkeys.clone());
-	part_2(monkeys);
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_11: (usize, fn() -> Result<()>) = (11, solution);
+use crate::generate::InputRng;
+use crate::lint::{self, Check};
+use crate::sections::Sections;
+use crate::{parse_all, solution, Answer, ParseResult, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{digit1, line_ending},
+	combinator::{cut, map, map_res},
+	multi::separated_list1,
+	sequence::{preceded, terminated, tuple},
+};
+use num::Integer;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+	Square,
+	MulConst(u64),
+	AddConst(u64),
+}
+
+use Operation::*;
+
+impl Operation {
+	pub fn evaluate(self, old: u64) -> Result<u64> {
+		match self {
+			Square => old.checked_mul(old),
+			MulConst(c) => old.checked_mul(c),
+			AddConst(c) => old.checked_add(c),
+		}
+		.ok_or_else(|| eyre!("worry level overflowed"))
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Monkey {
+	id: usize,
+	items: Vec<u64>,
+	operation: Operation,
+	test_modulo: u64,
+	target_ids: [usize; 2],
+	items_inspected: usize,
+}
+
+fn parse_monkey_id(i: &str) -> ParseResult<'_, usize> {
+	preceded(
+		tag("Monkey "),
+		cut(terminated(
+			map_res(digit1, str::parse),
+			tuple((tag(":"), line_ending)),
+		)),
+	)(i)
+}
+
+fn parse_monkey_items(i: &str) -> ParseResult<'_, Vec<u64>> {
+	preceded(
+		tag("  Starting items: "),
+		terminated(
+			separated_list1(tag(", "), map_res(digit1, str::parse)),
+			line_ending,
+		),
+	)(i)
+}
+
+fn parse_operation(i: &str) -> ParseResult<'_, Operation> {
+	preceded(
+		tag("  Operation: new = old "),
+		terminated(
+			alt((
+				map(tag("* old"), |_| Square),
+				map(preceded(tag("* "), map_res(digit1, str::parse)), MulConst),
+				map(preceded(tag("+ "), map_res(digit1, str::parse)), AddConst),
+			)),
+			line_ending,
+		),
+	)(i)
+}
+
+fn parse_test(i: &str) -> ParseResult<'_, u64> {
+	preceded(
+		tag("  Test: divisible by "),
+		terminated(map_res(digit1, str::parse), line_ending),
+	)(i)
+}
+
+fn parse_targets(i: &str) -> ParseResult<'_, [usize; 2]> {
+	map(
+		tuple((
+			preceded(
+				tag("    If true: throw to monkey "),
+				terminated(map_res(digit1, str::parse), line_ending),
+			),
+			preceded(
+				tag("    If false: throw to monkey "),
+				terminated(map_res(digit1, str::parse), line_ending),
+			),
+		)),
+		|(t, f)| [t, f],
+	)(i)
+}
+
+fn parse_monkey(i: &str) -> ParseResult<'_, Monkey> {
+	map(
+		// the rest has to follow once a monkey started
+		tuple((
+			parse_monkey_id,
+			cut(parse_monkey_items),
+			cut(parse_operation),
+			cut(parse_test),
+			cut(parse_targets),
+		)),
+		|(id, items, operation, test_modulo, target_ids)| Monkey {
+			id,
+			items,
+			operation,
+			test_modulo,
+			target_ids,
+			items_inspected: 0,
+		},
+	)(i)
+}
+
+fn read_monkeys(input: &str) -> Result<Vec<Monkey>> {
+	Sections::new(input)
+		.repeat(|sections| sections.block("monkey", |monkey| parse_all(monkey, parse_monkey)))
+}
+
+// the monkeys are looked up by their number, a monkey throwing to itself would have to borrow
+// itself twice, the tests divide by their number and the answer needs the two busiest monkeys
+fn check_monkeys(monkeys: &[Monkey]) -> Vec<Check> {
+	let count = if monkeys.len() < 2 {
+		Err(eyre!("found {} monkeys", monkeys.len()))
+	} else {
+		Ok(())
+	};
+	let sorted = match monkeys.iter().enumerate().find(|(i, m)| m.id != *i) {
+		Some((i, monkey)) => Err(eyre!("expected monkey {i}, found monkey {}", monkey.id)),
+		None => Ok(()),
+	};
+	let targets = match monkeys.iter().enumerate().find(|(i, m)| {
+		m.target_ids[0] == m.target_ids[1]
+			|| m.target_ids.iter().any(|&j| j >= monkeys.len() || *i == j)
+	}) {
+		Some((
+			i,
+			Monkey {
+				target_ids: [a, b], ..
+			},
+		)) => Err(eyre!("monkey {i} throws to monkeys {a} and {b}")),
+		None => Ok(()),
+	};
+	let divisors = match monkeys.iter().find(|m| m.test_modulo == 0) {
+		Some(monkey) => Err(eyre!("monkey {} tests divisibility by 0", monkey.id)),
+		None => Ok(()),
+	};
+	vec![
+		Check::new("there are at least two monkeys", count),
+		Check::new("the monkeys are sorted", sorted),
+		Check::new("every monkey throws to two other monkeys", targets),
+		Check::new("no monkey tests divisibility by 0", divisors),
+	]
+}
+
+fn parse_monkeys(input: &str) -> Result<Vec<Monkey>> {
+	let monkeys = read_monkeys(input)?;
+	lint::require(check_monkeys(&monkeys))?;
+	Ok(monkeys)
+}
+
+fn pick_disjoint_mut<T, const N: usize>(items: &mut [T], indices: [usize; N]) -> [&mut T; N] {
+	assert!(indices.iter().all(|&i| i < items.len()));
+	assert!(indices.iter().tuple_combinations().all(|(&i, &j)| i != j));
+	indices.map(|i| unsafe { &mut *items.as_mut_ptr().add(i) })
+}
+
+fn perform_round_1(monkeys: &mut [Monkey]) -> Result<()> {
+	for i in 0..monkeys.len() {
+		let [monkey, true_monkey, false_monkey] = pick_disjoint_mut(
+			monkeys,
+			[i, monkeys[i].target_ids[0], monkeys[i].target_ids[1]],
+		);
+
+		monkey.items_inspected += monkey.items.len();
+		for item in monkey.items.drain(..) {
+			let item = monkey.operation.evaluate(item)? / 3;
+			if item.is_multiple_of(monkey.test_modulo) {
+				true_monkey.items.push(item);
+			} else {
+				false_monkey.items.push(item);
+			}
+		}
+	}
+	Ok(())
+}
+
+fn part_1(mut monkeys: Vec<Monkey>) -> Result<usize> {
+	(0..20).try_for_each(|_| perform_round_1(&mut monkeys))?;
+	monkeys.select_nth_unstable_by(1, |a, b| b.items_inspected.cmp(&a.items_inspected));
+	Ok(monkeys[..2]
+		.iter()
+		.map(|m| m.items_inspected)
+		.product::<usize>())
+}
+
+fn perform_round_2(monkeys: &mut [Monkey], modulo: u64) -> Result<()> {
+	for i in 0..monkeys.len() {
+		let [monkey, true_monkey, false_monkey] = pick_disjoint_mut(
+			monkeys,
+			[i, monkeys[i].target_ids[0], monkeys[i].target_ids[1]],
+		);
+
+		monkey.items_inspected += monkey.items.len();
+		for item in monkey.items.drain(..) {
+			let item = monkey.operation.evaluate(item)? % modulo;
+			if item.is_multiple_of(monkey.test_modulo) {
+				true_monkey.items.push(item);
+			} else {
+				false_monkey.items.push(item);
+			}
+		}
+	}
+	Ok(())
+}
+
+fn part_2(mut monkeys: Vec<Monkey>) -> Result<usize> {
+	let modulo = monkeys
+		.iter()
+		.map(|m| m.test_modulo)
+		.reduce(|c, d| c.lcm(&d))
+		.unwrap_or(1);
+	(0..10000).try_for_each(|_| perform_round_2(&mut monkeys, modulo))?;
+	monkeys.select_nth_unstable_by(1, |a, b| b.items_inspected.cmp(&a.items_inspected));
+	Ok(monkeys[..2]
+		.iter()
+		.map(|m| m.items_inspected)
+		.product::<usize>())
+}
+
+struct Day11;
+
+impl Puzzle for Day11 {
+	type Parsed = Vec<Monkey>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		parse_monkeys(input)
+	}
+
+	fn part_1(monkeys: &Self::Parsed) -> Result<Answer> {
+		Ok(part_1(monkeys.clone())?.into())
+	}
+
+	fn part_2(monkeys: &Self::Parsed) -> Result<Answer> {
+		Ok(part_2(monkeys.clone())?.into())
+	}
+
+	fn lint(input: &str) -> Result<Vec<Check>> {
+		Ok(check_monkeys(&read_monkeys(input)?))
+	}
+
+	/// Generates `size` monkeys, one of which squares the worry levels like in the actual inputs.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let count = size.max(3);
+		// distinct primes keep the common multiple of part 2 small enough to square
+		let mut primes = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+		primes.shuffle(rng);
+		loop {
+			let squaring = rng.gen_range(0..count);
+			let monkeys: Vec<String> = (0..count)
+				.map(|id| {
+					let items = (0..rng.gen_range(1..=6))
+						.map(|_| rng.gen_range(50..=99).to_string())
+						.collect::<Vec<_>>()
+						.join(", ");
+					let operation = if id == squaring {
+						"* old".to_string()
+					} else if rng.gen_ratio(1, 3) {
+						format!("* {}", rng.gen_range(2..=19))
+					} else {
+						format!("+ {}", rng.gen_range(1..=8))
+					};
+					let mut targets: Vec<_> = (0..count).filter(|&target| target != id).collect();
+					targets.shuffle(rng);
+					format!(
+						"Monkey {id}:\n  Starting items: {items}\n  Operation: new = old {operation}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+						primes[id % primes.len()],
+						targets[0],
+						targets[1],
+					)
+				})
+				.collect();
+			let input = monkeys.join("\n");
+			// the worry levels of part 1 aren't kept small, and overflow if squared too often
+			if part_1(parse_monkeys(&input)?).is_ok() {
+				return Ok(input);
+			}
+		}
+	}
+}
+
+solution!(Day11, day = 11, title = "Monkey in the Middle");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = concat!(
+		"Monkey 0:\n",
+		"  Starting items: 79, 98\n",
+		"  Operation: new = old * 19\n",
+		"  Test: divisible by 23\n",
+		"    If true: throw to monkey 2\n",
+		"    If false: throw to monkey 3\n",
+		"\n",
+		"Monkey 1:\n",
+		"  Starting items: 54, 65, 75, 74\n",
+		"  Operation: new = old + 6\n",
+		"  Test: divisible by 19\n",
+		"    If true: throw to monkey 2\n",
+		"    If false: throw to monkey 0\n",
+		"\n",
+		"Monkey 2:\n",
+		"  Starting items: 79, 60, 97\n",
+		"  Operation: new = old * old\n",
+		"  Test: divisible by 13\n",
+		"    If true: throw to monkey 1\n",
+		"    If false: throw to monkey 3\n",
+		"\n",
+		"Monkey 3:\n",
+		"  Starting items: 74\n",
+		"  Operation: new = old + 3\n",
+		"  Test: divisible by 17\n",
+		"    If true: throw to monkey 0\n",
+		"    If false: throw to monkey 1\n",
+	);
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day11::solve(EXAMPLE)?, Answers::new(10605, 2713310158u64));
+		Ok(())
+	}
+
+	#[test]
+	fn unsolvable_monkeys_are_rejected() {
+		assert!(Day11::parse("").is_err());
+		assert!(Day11::parse(&EXAMPLE.replace("by 13", "by 0")).is_err());
+	}
+}
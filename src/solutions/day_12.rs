@@ -1,101 +1,123 @@
-use std::{fs::File, io::Read};
-
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use itertools::Itertools;
-use linkme::distributed_slice;
-use ndarray::{prelude::*, ErrorKind::IncompatibleShape, ShapeError};
-use pathfinding::directed::dijkstra::dijkstra;
-
-type Position = (usize, usize);
-
-fn read_map(file_name: &str) -> Result<(Array2<u8>, Position, Position)> {
-	let mut bytes = Vec::new();
-	File::open(file_name)?.read_to_end(&mut bytes)?;
-	let mut lines = 0usize;
-	let mut columns = None;
-	let mut start_position = None;
-	let mut end_position = None;
-	let values = bytes
-		.split_mut(|&b| b == b'\n')
-		.map(|line| {
-			if line.is_empty() {
-				return Ok(line);
-			}
-			lines += 1;
-			if let Some(columns) = columns {
-				if columns != line.len() {
-					return Err(ShapeError::from_kind(IncompatibleShape).into());
-				}
-			}
-			columns = Some(line.len());
-			line.iter_mut().enumerate().for_each(|(col, ch)| match ch {
-				b'S' => {
-					*ch = b'a';
-					start_position = Some((lines - 1, col));
-				}
-				b'E' => {
-					*ch = b'z';
-					end_position = Some((lines - 1, col));
-				}
-				_ => {}
-			});
-			Ok(line)
-		})
-		.flatten_ok()
-		.map_ok(|&mut b| b.checked_sub(b'a').ok_or_else(|| eyre!("invalid height")))
-		.flatten_ok()
-		.collect::<Result<Vec<_>>>()?;
-	Ok((
-		Array2::from_shape_vec((lines, columns.unwrap_or(0)), values)?,
-		start_position.ok_or_else(|| eyre!("no start position"))?,
-		end_position.ok_or_else(|| eyre!("no end position"))?,
-	))
-}
-
-fn solution() -> Result<()> {
-	let (map, start, end) = read_map("inputs/day-12")?;
-
-	let neighbors = |(r, c): (usize, usize)| {
-		[
-			r.checked_sub(1).map(|r| (r, c)),
-			c.checked_sub(1).map(|c| (r, c)),
-			(r + 1 < map.dim().0).then_some((r + 1, c)),
-			(c + 1 < map.dim().1).then_some((r, c + 1)),
-		]
-	};
-
-	let (_, cost) = dijkstra(
-		&start,
-		|&p| {
-			neighbors(p).into_iter().filter_map({
-				let hp1 = map[p] + 1;
-				let map = &map;
-				move |p: Option<(usize, usize)>| p.and_then(|p| (map[p] <= hp1).then_some((p, 1)))
-			})
-		},
-		|p| p == &end,
-	)
-	.ok_or_else(|| eyre!("no path to end"))?;
-	println!("{cost}");
-
-	let (_, cost) = dijkstra(
-		&end,
-		|&p| {
-			neighbors(p).into_iter().filter_map({
-				let h = map[p];
-				let map = &map;
-				move |p: Option<(usize, usize)>| p.and_then(|p| (h <= map[p] + 1).then_some((p, 1)))
-			})
-		},
-		|&p| map[p] == 0,
-	)
-	.ok_or_else(|| eyre!("no path to start"))?;
-	println!("{cost}");
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_12: (usize, fn() -> Result<()>) = (12, solution);
+use crate::generate::InputRng;
+use crate::geometry::grid_neighbors;
+use crate::grid::Grid;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::prelude::*;
+use pathfinding::directed::dijkstra::dijkstra;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+type Position = [usize; 2];
+
+fn read_map(input: &str) -> Result<(Array2<u8>, Position, Position)> {
+	let mut map = Grid::<u8>::parse(input)?;
+	let start = map.take_marker(b'S', b'a')?;
+	let end = map.take_marker(b'E', b'z')?;
+	let heights = map.try_map(|byte| byte.is_ascii_lowercase().then(|| byte - b'a'))?;
+	Ok((heights.into(), start, end))
+}
+
+fn neighbors(map: &Array2<u8>, position: Position) -> impl Iterator<Item = Position> {
+	let (rows, columns) = map.dim();
+	grid_neighbors(position, [rows, columns])
+}
+
+struct Day12;
+
+impl Puzzle for Day12 {
+	type Parsed = (Array2<u8>, Position, Position);
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_map(input)
+	}
+
+	fn part_1((map, start, end): &Self::Parsed) -> Result<Answer> {
+		let (_, cost) = dijkstra(
+			start,
+			|&p| {
+				neighbors(map, p).filter_map({
+					let hp1 = map[p] + 1;
+					move |p| (map[p] <= hp1).then_some((p, 1))
+				})
+			},
+			|p| p == end,
+		)
+		.ok_or_else(|| eyre!("no path to end"))?;
+		Ok(cost.into())
+	}
+
+	fn part_2((map, _, end): &Self::Parsed) -> Result<Answer> {
+		let (_, cost) = dijkstra(
+			end,
+			|&p| {
+				neighbors(map, p).filter_map({
+					let h = map[p];
+					move |p| (h <= map[p] + 1).then_some((p, 1))
+				})
+			},
+			|&p| map[p] == 0,
+		)
+		.ok_or_else(|| eyre!("no path to start"))?;
+		Ok(cost.into())
+	}
+
+	/// Generates a map `size` rows high and three times as wide, with a path climbing from the
+	/// start to the end one step at a time and random heights around it.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		// the path has to be 25 steps long at least to climb from a to z
+		let (rows, columns) = (size.max(9), 3 * size.max(9));
+		let mut map: Vec<Vec<u8>> = (0..rows)
+			.map(|_| (0..columns).map(|_| rng.gen_range(b'a'..=b'z')).collect())
+			.collect();
+		let (start, end) = (rng.gen_range(0..rows), rng.gen_range(0..rows));
+		let mut steps = vec![[0, 1]; columns - 1];
+		steps.extend(vec![[1, 0]; start.abs_diff(end)]);
+		steps.shuffle(rng);
+		let length = steps.len();
+		let mut position = [start, 0];
+		for (step, [down, right]) in steps.into_iter().enumerate() {
+			map[position[0]][position[1]] = b'a' + (25 * step / length) as u8;
+			position = [
+				if start < end {
+					position[0] + down
+				} else {
+					position[0] - down
+				},
+				position[1] + right,
+			];
+		}
+		map[start][0] = b'S';
+		map[end][columns - 1] = b'E';
+		Ok(map
+			.into_iter()
+			.map(|mut row| {
+				row.push(b'\n');
+				String::from_utf8(row)
+			})
+			.collect::<Result<_, _>>()?)
+	}
+}
+
+solution!(Day12, day = 12, title = "Hill Climbing Algorithm");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day12::solve(EXAMPLE)?, Answers::new(31, 29));
+		Ok(())
+	}
+}
@@ -1,98 +1,193 @@
-use std::cmp::Ordering;
-
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::Result;
-use linkme::distributed_slice;
-use nom::{
-	branch::alt,
-	character::complete::{char, digit1, line_ending},
-	combinator::{map, map_res},
-	multi::separated_list0,
-	sequence::{delimited, pair, terminated},
-	IResult,
-};
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-enum PacketData {
-	Integer(u8),
-	List(Vec<PacketData>),
-}
-
-impl PartialOrd for PacketData {
-	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		use PacketData::*;
-		match (self, other) {
-			(Integer(left), Integer(right)) => Some(left.cmp(right)),
-			(List(left), List(right)) => Some(left.cmp(right)),
-			(List(left), Integer(right)) => Some(left.as_slice().cmp([Integer(*right)].as_slice())),
-			(Integer(left), List(right)) => Some([Integer(*left)].as_slice().cmp(right.as_slice())),
-		}
-	}
-}
-
-impl Ord for PacketData {
-	fn cmp(&self, other: &Self) -> Ordering {
-		self.partial_cmp(other).expect("unreachable")
-	}
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Packet(Vec<PacketData>);
-
-fn parse_packet_data(i: &str) -> IResult<&str, PacketData, ()> {
-	alt((
-		map(parse_packet_data_list, PacketData::List),
-		map_res(digit1, |s: &str| {
-			s.parse().map(PacketData::Integer).map_err(|_| ())
-		}),
-	))(i)
-}
-
-fn parse_packet_data_list(i: &str) -> IResult<&str, Vec<PacketData>, ()> {
-	delimited(
-		char('['),
-		separated_list0(char(','), parse_packet_data),
-		char(']'),
-	)(i)
-}
-
-fn parse_packet(i: &str) -> IResult<&str, Packet, ()> {
-	map(terminated(parse_packet_data_list, line_ending), Packet)(i)
-}
-
-fn parse_input(i: &str) -> IResult<&str, Vec<(Packet, Packet)>, ()> {
-	separated_list0(line_ending, pair(parse_packet, parse_packet))(i)
-}
-
-fn solution() -> Result<()> {
-	let input = std::fs::read_to_string("inputs/day-13")?;
-	let (_, packets) = parse_input(&input)?;
-	let correct = packets
-		.iter()
-		.enumerate()
-		.filter(|(_, (left, right))| left < right)
-		.map(|(i, _)| i + 1)
-		.sum::<usize>();
-	println!("{correct}");
-
-	let markers = [
-		Packet(vec![PacketData::List(vec![PacketData::Integer(2)])]),
-		Packet(vec![PacketData::List(vec![PacketData::Integer(6)])]),
-	];
-	let mut packets: Vec<_> = packets
-		.into_iter()
-		.flat_map(|(a, b)| [a, b])
-		.chain(markers.clone())
-		.collect();
-	packets.sort();
-	let key = markers
-		.iter()
-		.map(|m| packets.binary_search(m).expect("unreachable") + 1)
-		.product::<usize>();
-	println!("{key}");
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_13: (usize, fn() -> Result<()>) = (13, solution);
+use std::cmp::Ordering;
+
+use crate::generate::InputRng;
+use crate::sections::Sections;
+use crate::{parse_all, solution, Answer, ParseResult, Puzzle};
+
+use color_eyre::eyre::Result;
+use nom::{
+	branch::alt,
+	character::complete::{char, digit1, line_ending},
+	combinator::{cut, map, map_res},
+	error::{VerboseError, VerboseErrorKind},
+	multi::separated_list0,
+	sequence::{delimited, pair, terminated},
+};
+use rand::Rng;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PacketData {
+	Integer(u8),
+	List(Vec<PacketData>),
+}
+
+impl PartialOrd for PacketData {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for PacketData {
+	fn cmp(&self, other: &Self) -> Ordering {
+		use PacketData::*;
+		match (self, other) {
+			(Integer(left), Integer(right)) => left.cmp(right),
+			(List(left), List(right)) => left.cmp(right),
+			(List(left), Integer(right)) => left.as_slice().cmp([Integer(*right)].as_slice()),
+			(Integer(left), List(right)) => [Integer(*left)].as_slice().cmp(right.as_slice()),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Packet(Vec<PacketData>);
+
+// deeper lists would overflow the stack before the parser gets to the end of them
+const MAX_DEPTH: usize = 256;
+
+fn parse_packet_data(i: &str, depth: usize) -> ParseResult<'_, PacketData> {
+	alt((
+		map(|i| parse_packet_data_list(i, depth), PacketData::List),
+		map_res(digit1, |s: &str| s.parse().map(PacketData::Integer)),
+	))(i)
+}
+
+fn parse_packet_data_list(i: &str, depth: usize) -> ParseResult<'_, Vec<PacketData>> {
+	if depth >= MAX_DEPTH && i.starts_with('[') {
+		return Err(nom::Err::Failure(VerboseError {
+			errors: vec![(
+				i,
+				VerboseErrorKind::Context("lists nested at most 256 deep"),
+			)],
+		}));
+	}
+	// anything but a list is an error once it started
+	delimited(
+		char('['),
+		cut(separated_list0(char(','), |i| {
+			parse_packet_data(i, depth + 1)
+		})),
+		cut(char(']')),
+	)(i)
+}
+
+fn parse_packet(i: &str) -> ParseResult<'_, Packet> {
+	map(
+		terminated(|i| parse_packet_data_list(i, 0), line_ending),
+		Packet,
+	)(i)
+}
+
+fn parse_input(input: &str) -> Result<Vec<(Packet, Packet)>> {
+	Sections::new(input).repeat(|sections| {
+		sections.block("pair", |packets| {
+			parse_all(packets, pair(parse_packet, parse_packet))
+		})
+	})
+}
+
+struct Day13;
+
+impl Puzzle for Day13 {
+	type Parsed = Vec<(Packet, Packet)>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		parse_input(input)
+	}
+
+	fn part_1(packets: &Self::Parsed) -> Result<Answer> {
+		let correct = packets
+			.iter()
+			.enumerate()
+			.filter(|(_, (left, right))| left < right)
+			.map(|(i, _)| i + 1)
+			.sum::<usize>();
+		Ok(correct.into())
+	}
+
+	fn part_2(packets: &Self::Parsed) -> Result<Answer> {
+		let markers = [
+			Packet(vec![PacketData::List(vec![PacketData::Integer(2)])]),
+			Packet(vec![PacketData::List(vec![PacketData::Integer(6)])]),
+		];
+		let mut packets: Vec<_> = packets
+			.iter()
+			.flat_map(|(a, b)| [a.clone(), b.clone()])
+			.chain(markers.clone())
+			.collect();
+		packets.sort();
+		let key = markers
+			.iter()
+			.map(|m| packets.binary_search(m).expect("unreachable") + 1)
+			.product::<usize>();
+		Ok(key.into())
+	}
+
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		fn list(rng: &mut InputRng, depth: usize) -> String {
+			let items: Vec<String> = (0..rng.gen_range(0..=4))
+				.map(|_| {
+					if depth < 4 && rng.gen_ratio(1, 3) {
+						list(rng, depth + 1)
+					} else {
+						rng.gen_range(0..=10).to_string()
+					}
+				})
+				.collect();
+			format!("[{}]", items.join(","))
+		}
+		let pairs: Vec<String> = (0..size.max(1))
+			.map(|_| format!("{}\n{}\n", list(rng, 0), list(rng, 0)))
+			.collect();
+		Ok(pairs.join("\n"))
+	}
+}
+
+solution!(Day13, day = 13, title = "Distress Signal");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day13::solve(EXAMPLE)?, Answers::new(13, 140));
+		Ok(())
+	}
+
+	#[test]
+	fn nesting_is_limited() {
+		let nested = |depth| format!("{}{}\n[]\n", "[".repeat(depth), "]".repeat(depth));
+		assert!(Day13::parse(&nested(MAX_DEPTH)).is_ok());
+		assert!(Day13::parse(&nested(MAX_DEPTH + 1)).is_err());
+		// far deeper than the stack could take
+		assert!(Day13::parse(&nested(200_000)).is_err());
+	}
+}
@@ -1,97 +1,243 @@
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use gcollections::ops::{Bounded, Cardinality, Difference, Empty, IsEmpty, Union};
-use interval::{interval_set::ToIntervalSet, IntervalSet};
-use linkme::distributed_slice;
-
-fn read_input() -> Result<Vec<([i64; 2], [i64; 2])>> {
-	let mut result = Vec::new();
-	try_for_each_line_in_file("inputs/day-15", |line| {
-		let (sx, line) = line
-			.trim()
-			.strip_prefix("Sensor at x=")
-			.ok_or_else(|| eyre!("invalid input prefix"))?
-			.split_once(", y=")
-			.ok_or_else(|| eyre!("can't split sensor position components"))?;
-		let (sy, line) = line
-			.split_once(": closest beacon is at x=")
-			.ok_or_else(|| eyre!("can't split sensor and beacon positions"))?;
-		let (bx, by) = line
-			.split_once(", y=")
-			.ok_or_else(|| eyre!("can't split beacon components"))?;
-		let [sx, sy, bx, by] = [sx, sy, bx, by].map(str::parse::<i64>);
-		let [sx, sy, bx, by] = [sx?, sy?, bx?, by?];
-		result.push(([sx, sy], [bx, by]));
-		Ok(())
-	})?;
-	Ok(result)
-}
-
-fn intervals_in_line(
-	input: &[([i64; 2], [i64; 2])],
-	y_ref: i64,
-	subtract_existing: bool,
-) -> IntervalSet<i64> {
-	let mut intervals = IntervalSet::<i64>::empty();
-
-	// add intervals within reach (#)
-	input
-		.iter()
-		.copied()
-		.filter_map(|([sx, sy], [bx, by])| {
-			let d = sx.abs_diff(bx) + sy.abs_diff(by);
-			let d_ref = sy.abs_diff(y_ref);
-			if d_ref > d {
-				return None;
-			}
-			let dd = (d - d_ref) as i64;
-			Some((sx - dd, sx + dd))
-		})
-		.for_each(|interval| {
-			intervals = intervals.union(&interval.to_interval_set());
-		});
-
-	// subtract existing beacons (B)
-	if subtract_existing {
-		input
-			.iter()
-			.copied()
-			.filter_map(|(_, [bx, by])| (by == y_ref).then_some((bx, bx)))
-			.for_each(|interval| {
-				intervals = intervals.difference(&interval.to_interval_set());
-			});
-	}
-
-	intervals
-}
-
-fn solution() -> Result<()> {
-	let input = read_input()?;
-	let y_ref = 2000000;
-	println!("{}", intervals_in_line(&input, y_ref, true).size());
-
-	let mut found = false;
-	for y_ref in 0..=4000000 {
-		let intervals = intervals_in_line(&input, y_ref, false);
-		let remaining = (0, 4000000).to_interval_set().difference(&intervals);
-		if remaining.is_empty() {
-			continue;
-		}
-		if remaining.size() != 1 || found {
-			return Err(eyre!("more than one possible sensor position"));
-		}
-
-		let interval = remaining.into_iter().next().expect("unreachable");
-		let x = interval.lower();
-		let tuning_frequency = 4000000 * x + y_ref;
-		println!("{tuning_frequency}");
-		found = true;
-	}
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_15: (usize, fn() -> Result<()>) = (15, solution);
+use crate::cancel::CancellationToken;
+use crate::generate::InputRng;
+use crate::geometry::Point;
+use crate::try_for_each_line;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use gcollections::ops::{Bounded, Cardinality, Difference, Empty, IsEmpty, Union};
+use interval::{interval_set::ToIntervalSet, IntervalSet};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+fn read_input(input: &str) -> Result<Vec<(Point<2>, Point<2>)>> {
+	let mut result = Vec::new();
+	try_for_each_line(input, |line| {
+		let (sx, line) = line
+			.trim()
+			.strip_prefix("Sensor at x=")
+			.ok_or_else(|| eyre!("invalid input prefix"))?
+			.split_once(", y=")
+			.ok_or_else(|| eyre!("can't split sensor position components"))?;
+		let (sy, line) = line
+			.split_once(": closest beacon is at x=")
+			.ok_or_else(|| eyre!("can't split sensor and beacon positions"))?;
+		let (bx, by) = line
+			.split_once(", y=")
+			.ok_or_else(|| eyre!("can't split beacon components"))?;
+		let [sx, sy, bx, by] = [sx, sy, bx, by].map(str::parse::<i64>);
+		let [sx, sy, bx, by] = [sx?, sy?, bx?, by?];
+		result.push((Point([sx, sy]), Point([bx, by])));
+		Ok(())
+	})?;
+	Ok(result)
+}
+
+fn intervals_in_line(
+	input: &[(Point<2>, Point<2>)],
+	y_ref: i64,
+	subtract_existing: bool,
+) -> IntervalSet<i64> {
+	let mut intervals = IntervalSet::<i64>::empty();
+
+	// add intervals within reach (#)
+	input
+		.iter()
+		.copied()
+		.filter_map(|(sensor, beacon)| {
+			let Point([sx, sy]) = sensor;
+			let d = sensor.manhattan(beacon);
+			let d_ref = (sy - y_ref).abs();
+			if d_ref > d {
+				return None;
+			}
+			let dd = d - d_ref;
+			Some((sx - dd, sx + dd))
+		})
+		.for_each(|interval| {
+			intervals = intervals.union(&interval.to_interval_set());
+		});
+
+	// subtract existing beacons (B)
+	if subtract_existing {
+		input
+			.iter()
+			.copied()
+			.filter_map(|(_, Point([bx, by]))| (by == y_ref).then_some((bx, bx)))
+			.for_each(|interval| {
+				intervals = intervals.difference(&interval.to_interval_set());
+			});
+	}
+
+	intervals
+}
+
+const Y_REF: i64 = 2000000;
+const SEARCH_SIZE: i64 = 4000000;
+
+fn excluded_positions(input: &[(Point<2>, Point<2>)], y_ref: i64) -> u64 {
+	intervals_in_line(input, y_ref, true).size()
+}
+
+fn tuning_frequency(
+	input: &[(Point<2>, Point<2>)],
+	search_size: i64,
+	cancel: &CancellationToken,
+) -> Result<i64> {
+	let mut found = None;
+	for y_ref in 0..=search_size {
+		cancel.check()?;
+		let intervals = intervals_in_line(input, y_ref, false);
+		let remaining = (0, search_size).to_interval_set().difference(&intervals);
+		if remaining.is_empty() {
+			continue;
+		}
+		if remaining.size() != 1 || found.is_some() {
+			return Err(eyre!("more than one possible sensor position"));
+		}
+
+		let interval = remaining.into_iter().next().expect("unreachable");
+		let x = interval.lower();
+		let tuning_frequency = 4000000 * x + y_ref;
+		found = Some(tuning_frequency);
+	}
+
+	found.ok_or_else(|| eyre!("no possible sensor position"))
+}
+
+struct Day15;
+
+impl Puzzle for Day15 {
+	type Parsed = Vec<(Point<2>, Point<2>)>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_input(input)
+	}
+
+	fn part_1(input: &Self::Parsed) -> Result<Answer> {
+		Ok(excluded_positions(input, Y_REF).into())
+	}
+
+	fn part_2(input: &Self::Parsed) -> Result<Answer> {
+		let cancel = CancellationToken::current();
+		Ok(tuning_frequency(input, SEARCH_SIZE, &cancel)?.into())
+	}
+
+	/// Generates `size` sensors around a distress beacon, and eight more far away that rule out
+	/// every other position in the search area, see [`generate_sensors`].
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		Ok(generate_sensors(rng, size, SEARCH_SIZE)
+			.into_iter()
+			.map(|(Point([sx, sy]), Point([bx, by]))| {
+				format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}\n")
+			})
+			.collect())
+	}
+}
+
+/// Hides a distress beacon in the search area, with `size` sensors around it and eight more far
+/// away that leave it as the only position they don't rule out: four that reach up to the beacon
+/// from the sides, and four that cover the diagonals through it.
+fn generate_sensors(
+	rng: &mut InputRng,
+	size: usize,
+	search_size: i64,
+) -> Vec<(Point<2>, Point<2>)> {
+	let hidden = Point([
+		rng.gen_range(0..=search_size),
+		rng.gen_range(0..=search_size),
+	]);
+	let reach = search_size + rng.gen_range(1..=1000);
+	let mut sensors = Vec::new();
+	for direction in [Point([1, 0]), Point([0, 1]), Point([-1, 0]), Point([0, -1])] {
+		sensors.push((hidden + direction * reach, hidden + direction));
+		let diagonal = Point([direction[0] - direction[1], direction[0] + direction[1]]);
+		sensors.push((hidden + diagonal * reach, hidden + direction));
+	}
+	for _ in 0..size {
+		let sensor = Point([
+			rng.gen_range(0..=search_size),
+			rng.gen_range(0..=search_size),
+		]);
+		// the beacon is closer than the distress beacon, so the sensor doesn't rule it out
+		let limit = sensor.manhattan(hidden);
+		if limit < 2 {
+			continue;
+		}
+		let distance = rng.gen_range(1..limit);
+		let dx = rng.gen_range(-distance..=distance);
+		let dy = (distance - dx.abs()) * if rng.gen_bool(0.5) { 1 } else { -1 };
+		sensors.push((sensor, sensor + Point([dx, dy])));
+	}
+	sensors.shuffle(rng);
+	sensors
+}
+
+solution!(Day15, day = 15, title = "Beacon Exclusion Zone");
+
+#[cfg(test)]
+mod tests {
+	use rand::SeedableRng;
+
+	use super::*;
+
+	const EXAMPLE: &str = "\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		let input = read_input(EXAMPLE)?;
+		assert_eq!(excluded_positions(&input, 10), 26);
+		let cancel = CancellationToken::default();
+		assert_eq!(tuning_frequency(&input, 20, &cancel)?, 56000011);
+		cancel.cancel();
+		assert!(tuning_frequency(&input, 20, &cancel).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn generated_sensors_leave_one_position() -> Result<()> {
+		// part 2 searches a smaller area just as well, and errors unless exactly one position is left
+		let cancel = CancellationToken::default();
+		for seed in 0..100 {
+			let mut rng = InputRng::seed_from_u64(seed);
+			let sensors = generate_sensors(&mut rng, seed as usize % 20, 20);
+			tuning_frequency(&sensors, 20, &cancel)?;
+		}
+		Ok(())
+	}
+
+	#[cfg(feature = "alloc-stats")]
+	#[test]
+	fn intervals_allocation_budget() -> Result<()> {
+		let input = read_input(EXAMPLE)?;
+		// intervallum allocates a new set for every interval turned into a set and every union or
+		// difference, about two per sensor and line; these are the counts measured on the example
+		let (excluded, stats) = crate::alloc::measure_thread(|| excluded_positions(&input, 10));
+		assert_eq!(excluded, 26);
+		let stats = stats.expect("allocations are counted");
+		assert!(stats.allocations <= 31, "{stats:?}");
+
+		let cancel = CancellationToken::default();
+		let (frequency, stats) =
+			crate::alloc::measure_thread(|| tuning_frequency(&input, 20, &cancel));
+		assert_eq!(frequency?, 56000011);
+		let stats = stats.expect("allocations are counted");
+		assert!(stats.allocations <= 451, "{stats:?}");
+		Ok(())
+	}
+}
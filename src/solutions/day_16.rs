@@ -1,221 +1,437 @@
-use std::collections::HashMap;
-
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use itertools::Itertools;
-use linkme::distributed_slice;
-use pathfinding::directed::dijkstra::dijkstra;
-use regex::Regex;
-
-type GraphEntry = (u64, Vec<(usize, u64)>);
-
-fn read_graph() -> Result<Vec<GraphEntry>> {
-	let mut nodes = HashMap::<_, _>::from_iter([("AA".to_string(), 0)]);
-	let mut graph = Vec::new();
-	let pattern = Regex::new(
-		r"Valve ([A-Z]{2}) has flow rate=(\d+); tunnel(?:s?) lead(?:s?) to valve(?:s?) ([A-Z, ]+)",
-	)?;
-	try_for_each_line_in_file("inputs/day-16", |line| {
-		let line = line.trim();
-		let captures = pattern
-			.captures(line)
-			.ok_or_else(|| eyre!("unexpected input '{line}'"))?;
-		let node = captures.get(1).unwrap().as_str();
-		let num_nodes = nodes.len();
-		let node = *nodes.entry(node.to_string()).or_insert(num_nodes);
-		let flow = captures.get(2).unwrap().as_str().parse::<u64>()?;
-		let neighbors: Vec<_> = captures
-			.get(3)
-			.unwrap()
-			.as_str()
-			.split(", ")
-			.map(|neighbor| {
-				let num_nodes = nodes.len();
-				let neighbor = *nodes.entry(neighbor.to_string()).or_insert(num_nodes);
-				(neighbor, 1u64)
-			})
-			.collect();
-		graph.push((node, flow, neighbors));
-		Ok(())
-	})?;
-	graph.sort_by_key(|(node, _, _)| *node);
-	let graph = graph
-		.into_iter()
-		.map(|(_, flow, mut neighbors)| {
-			neighbors.sort();
-			(flow, neighbors)
-		})
-		.collect();
-	Ok(graph)
-}
-
-fn compact_graph(
-	graph: &[GraphEntry],
-	mut condition: impl FnMut(usize, u64) -> bool,
-) -> Vec<GraphEntry> {
-	let nodes_to_fold: Vec<_> = graph
-		.iter()
-		.enumerate()
-		.skip(1)
-		.filter_map(|(node, (flow, _))| condition(node, *flow).then_some(node))
-		.collect();
-	let mut graph =
-		HashMap::<_, _>::from_iter(graph.iter().enumerate().map(|(node, (flow, neighbors))| {
-			let neighbors = HashMap::<_, _>::from_iter(neighbors.iter().copied());
-			(node, (*flow, neighbors))
-		}));
-	for node in nodes_to_fold {
-		let (_, neighbors) = graph.remove(&node).unwrap();
-		for (neighbor, distance) in &neighbors {
-			let current = &mut graph.get_mut(neighbor).unwrap().1;
-			let _ = current.remove(&node);
-			for (other_neighbor, other_distance) in &neighbors {
-				if neighbor == other_neighbor {
-					continue;
-				}
-				let total_distance = distance + other_distance;
-				let distance = current.entry(*other_neighbor).or_insert(total_distance);
-				*distance = (*distance).min(total_distance);
-			}
-		}
-	}
-
-	let mut nodes = HashMap::<_, _>::with_capacity(graph.len());
-	nodes.insert(0usize, 0usize);
-	let mut graph: Vec<_> = graph
-		.into_iter()
-		.map(|(node, (flow, neighbors))| {
-			let num_nodes = nodes.len();
-			let node = *nodes.entry(node).or_insert(num_nodes);
-			let mut neighbors: Vec<_> = neighbors
-				.into_iter()
-				.map(|(neighbor, distance)| {
-					let num_nodes = nodes.len();
-					let neighbor = *nodes.entry(neighbor).or_insert(num_nodes);
-					(neighbor, distance)
-				})
-				.collect();
-			neighbors.sort_by_key(|(neighbor, _)| *neighbor);
-			(node, flow, neighbors)
-		})
-		.collect();
-	graph.sort_by_key(|(node, _, _)| *node);
-	graph
-		.into_iter()
-		.map(|(_, flow, neighbors)| (flow, neighbors))
-		.collect()
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
-struct State {
-	node: usize,
-	flow: u64,
-	accumulated_flow: u64,
-	open: u64,
-	time: u64,
-}
-
-fn solve(graph: &[GraphEntry], t_max: u64) -> Result<u64> {
-	let num_nodes = graph.len();
-	let total_max_flow = graph.iter().map(|(flow, _)| *flow).sum::<u64>();
-
-	if num_nodes == 0 {
-		return Err(eyre!("graph unexpectedly empty"));
-	}
-	if num_nodes > 64 {
-		return Err(eyre!("graph unexpectedly large"));
-	}
-
-	let state = State::default();
-	let path = dijkstra(
-		&state,
-		|state| {
-			let State {
-				node,
-				flow,
-				accumulated_flow,
-				open,
-				time,
-			} = *state;
-			let node_mask = 1 << node;
-			let open_if_closed = (node != 0 && (open & node_mask) == 0).then(|| {
-				(
-					State {
-						node,
-						flow: flow + graph[node].0,
-						accumulated_flow: accumulated_flow + flow,
-						open: open | node_mask,
-						time: time + 1,
-					},
-					total_max_flow - flow,
-				)
-			});
-			let do_nothing = (open == (1 << num_nodes) - 2
-				|| graph[node]
-					.1
-					.iter()
-					.all(|(_, distance)| time + distance > t_max))
-			.then(|| {
-				let dt = t_max - time;
-				(
-					State {
-						node,
-						flow,
-						accumulated_flow: accumulated_flow + dt * flow,
-						open,
-						time: t_max,
-					},
-					dt * (total_max_flow - flow),
-				)
-			});
-			graph[node]
-				.1
-				.iter()
-				.copied()
-				.filter_map(move |(neighbor, distance)| {
-					(open != (1 << num_nodes) - 2 && time + distance <= t_max).then_some((
-						State {
-							node: neighbor,
-							flow,
-							accumulated_flow: accumulated_flow + distance * flow,
-							open,
-							time: time + distance,
-						},
-						distance * (total_max_flow - flow),
-					))
-				})
-				.chain(open_if_closed)
-				.chain(do_nothing)
-		},
-		|state| state.time == t_max,
-	)
-	.ok_or_else(|| eyre!("no solution found!"))?;
-	Ok(path.0.last().unwrap().accumulated_flow)
-}
-
-fn solution() -> Result<()> {
-	let graph = read_graph()?;
-	let graph = compact_graph(&graph, |_, flow| flow == 0);
-
-	let flow = solve(&graph, 30)?;
-	println!("{flow}");
-
-	let n = graph.len();
-	let flow = (1..n)
-		.combinations(n / 2)
-		.map(|me| {
-			let my_graph = compact_graph(&graph, |node, _| me.contains(&node));
-			let elephants_graph = compact_graph(&graph, |node, _| !me.contains(&node));
-			solve(&my_graph, 26).unwrap() + solve(&elephants_graph, 26).unwrap()
-		})
-		.max()
-		.unwrap();
-	println!("{flow}");
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_16: (usize, fn() -> Result<()>) = (16, solution);
+use std::collections::HashMap;
+
+use crate::cancel::CancellationToken;
+use crate::generate::InputRng;
+use crate::lint::{self, Check};
+use crate::{capture, try_for_each_line};
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use pathfinding::directed::dijkstra::dijkstra;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::Regex;
+
+type GraphEntry = (u64, Vec<(usize, u64)>);
+
+static VALVE: Lazy<Regex> = Lazy::new(|| {
+	Regex::new(
+		r"^Valve ([A-Z]{2}) has flow rate=(\d+); tunnel(?:s?) lead(?:s?) to valve(?:s?) ([A-Z, ]+)$",
+	)
+	.expect("valid pattern")
+});
+
+struct Valve {
+	name: String,
+	flow: u64,
+	tunnels: Vec<String>,
+}
+
+fn read_valves(input: &str) -> Result<Vec<Valve>> {
+	let mut valves = Vec::new();
+	try_for_each_line(input, |line| {
+		let line = line.trim();
+		let captures = VALVE
+			.captures(line)
+			.ok_or_else(|| eyre!("unexpected input '{line}'"))?;
+		valves.push(Valve {
+			name: capture(&captures, 1)?.to_string(),
+			flow: capture(&captures, 2)?.parse()?,
+			tunnels: capture(&captures, 3)?
+				.split(", ")
+				.map(str::to_string)
+				.collect(),
+		});
+		Ok(())
+	})?;
+	Ok(valves)
+}
+
+// every valve, including AA and the ones only mentioned as neighbors, needs exactly one line, and
+// folding valves away relies on tunnels going both ways
+fn check_valves(valves: &[Valve]) -> Vec<Check> {
+	let mut described = HashMap::new();
+	let twice = valves
+		.iter()
+		.find(|valve| described.insert(valve.name.as_str(), *valve).is_some());
+	let missing = ["AA"]
+		.into_iter()
+		.chain(
+			valves
+				.iter()
+				.flat_map(|valve| valve.tunnels.iter().map(String::as_str)),
+		)
+		.find(|name| !described.contains_key(name));
+	let once = match (twice, missing) {
+		(Some(valve), _) => Err(eyre!("valve {} is described more than once", valve.name)),
+		(None, Some(name)) => Err(eyre!("valve {name} isn't described")),
+		(None, None) => Ok(()),
+	};
+	let one_way = valves.iter().find_map(|valve| {
+		valve
+			.tunnels
+			.iter()
+			.find(|&tunnel| {
+				*tunnel == valve.name
+					|| described
+						.get(tunnel.as_str())
+						.is_some_and(|other| !other.tunnels.contains(&valve.name))
+			})
+			.map(|tunnel| (valve, tunnel))
+	});
+	let both_ways = match one_way {
+		Some((valve, tunnel)) if *tunnel == valve.name => {
+			Err(eyre!("a tunnel leads from {tunnel} to itself"))
+		}
+		Some((valve, tunnel)) => Err(eyre!(
+			"a tunnel leads from {} to {tunnel} but not back",
+			valve.name
+		)),
+		None => Ok(()),
+	};
+	vec![
+		Check::new("every valve is described once", once),
+		Check::new("every tunnel connects two valves both ways", both_ways),
+	]
+}
+
+fn check_flows(valves: &[Valve]) -> Vec<Check> {
+	// the open valves are a bitmask with a bit for every valve but AA, and `1 << num_nodes` has
+	// to fit as well
+	let mask = match valves
+		.iter()
+		.filter(|valve| valve.name != "AA" && valve.flow > 0)
+		.count()
+	{
+		0..=62 => Ok(()),
+		flowing => Err(eyre!("there are {flowing} valves with flow")),
+	};
+	// AA is never opened
+	let start = match valves.iter().find(|valve| valve.name == "AA") {
+		Some(Valve { flow: 0, .. }) | None => Ok(()),
+		Some(Valve { flow, .. }) => Err(eyre!("AA has a flow rate of {flow}")),
+	};
+	vec![
+		Check::new("at most 62 valves have flow", mask),
+		Check::new("valve AA has no flow", start),
+	]
+}
+
+/// The valves with AA first, each with its flow and the valves its tunnels lead to.
+fn build_graph(valves: &[Valve]) -> Vec<GraphEntry> {
+	let mut valves: Vec<_> = valves.iter().collect();
+	valves.sort_by_key(|valve| valve.name != "AA");
+	let nodes: HashMap<_, _> = valves
+		.iter()
+		.enumerate()
+		.map(|(node, valve)| (valve.name.as_str(), node))
+		.collect();
+	valves
+		.iter()
+		.map(|valve| {
+			let mut neighbors: Vec<_> = valve
+				.tunnels
+				.iter()
+				.map(|tunnel| (nodes[tunnel.as_str()], 1))
+				.collect();
+			neighbors.sort();
+			(valve.flow, neighbors)
+		})
+		.collect()
+}
+
+fn read_graph(input: &str) -> Result<Vec<GraphEntry>> {
+	let valves = read_valves(input)?;
+	lint::require(check_valves(&valves))?;
+	Ok(build_graph(&valves))
+}
+
+fn compact_graph(
+	graph: &[GraphEntry],
+	mut condition: impl FnMut(usize, u64) -> bool,
+) -> Vec<GraphEntry> {
+	let nodes_to_fold: Vec<_> = graph
+		.iter()
+		.enumerate()
+		.skip(1)
+		.filter_map(|(node, (flow, _))| condition(node, *flow).then_some(node))
+		.collect();
+	let mut graph =
+		HashMap::<_, _>::from_iter(graph.iter().enumerate().map(|(node, (flow, neighbors))| {
+			let neighbors = HashMap::<_, _>::from_iter(neighbors.iter().copied());
+			(node, (*flow, neighbors))
+		}));
+	for node in nodes_to_fold {
+		let (_, neighbors) = graph.remove(&node).unwrap();
+		for (neighbor, distance) in &neighbors {
+			let current = &mut graph.get_mut(neighbor).unwrap().1;
+			let _ = current.remove(&node);
+			for (other_neighbor, other_distance) in &neighbors {
+				if neighbor == other_neighbor {
+					continue;
+				}
+				let total_distance = distance + other_distance;
+				let distance = current.entry(*other_neighbor).or_insert(total_distance);
+				*distance = (*distance).min(total_distance);
+			}
+		}
+	}
+
+	let mut nodes = HashMap::<_, _>::with_capacity(graph.len());
+	nodes.insert(0usize, 0usize);
+	let mut graph: Vec<_> = graph
+		.into_iter()
+		.map(|(node, (flow, neighbors))| {
+			let num_nodes = nodes.len();
+			let node = *nodes.entry(node).or_insert(num_nodes);
+			let mut neighbors: Vec<_> = neighbors
+				.into_iter()
+				.map(|(neighbor, distance)| {
+					let num_nodes = nodes.len();
+					let neighbor = *nodes.entry(neighbor).or_insert(num_nodes);
+					(neighbor, distance)
+				})
+				.collect();
+			neighbors.sort_by_key(|(neighbor, _)| *neighbor);
+			(node, flow, neighbors)
+		})
+		.collect();
+	graph.sort_by_key(|(node, _, _)| *node);
+	graph
+		.into_iter()
+		.map(|(_, flow, neighbors)| (flow, neighbors))
+		.collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+struct State {
+	node: usize,
+	flow: u64,
+	accumulated_flow: u64,
+	open: u64,
+	time: u64,
+}
+
+fn maximum_flow(graph: &[GraphEntry], t_max: u64) -> Result<u64> {
+	let num_nodes = graph.len();
+	let total_max_flow = graph.iter().map(|(flow, _)| *flow).sum::<u64>();
+
+	if num_nodes == 0 {
+		return Err(eyre!("graph unexpectedly empty"));
+	}
+	if num_nodes >= 64 {
+		return Err(eyre!("graph unexpectedly large"));
+	}
+
+	let state = State::default();
+	let path = dijkstra(
+		&state,
+		|state| {
+			let State {
+				node,
+				flow,
+				accumulated_flow,
+				open,
+				time,
+			} = *state;
+			let node_mask = 1 << node;
+			let open_if_closed = (node != 0 && (open & node_mask) == 0).then(|| {
+				(
+					State {
+						node,
+						flow: flow + graph[node].0,
+						accumulated_flow: accumulated_flow + flow,
+						open: open | node_mask,
+						time: time + 1,
+					},
+					total_max_flow - flow,
+				)
+			});
+			let do_nothing = (open == (1 << num_nodes) - 2
+				|| graph[node]
+					.1
+					.iter()
+					.all(|(_, distance)| time + distance > t_max))
+			.then(|| {
+				let dt = t_max - time;
+				(
+					State {
+						node,
+						flow,
+						accumulated_flow: accumulated_flow + dt * flow,
+						open,
+						time: t_max,
+					},
+					dt * (total_max_flow - flow),
+				)
+			});
+			graph[node]
+				.1
+				.iter()
+				.copied()
+				.filter_map(move |(neighbor, distance)| {
+					(open != (1 << num_nodes) - 2 && time + distance <= t_max).then_some((
+						State {
+							node: neighbor,
+							flow,
+							accumulated_flow: accumulated_flow + distance * flow,
+							open,
+							time: time + distance,
+						},
+						distance * (total_max_flow - flow),
+					))
+				})
+				.chain(open_if_closed)
+				.chain(do_nothing)
+		},
+		|state| state.time == t_max,
+	)
+	.ok_or_else(|| eyre!("no solution found!"))?;
+	Ok(path.0.last().unwrap().accumulated_flow)
+}
+
+struct Day16;
+
+impl Puzzle for Day16 {
+	type Parsed = Vec<GraphEntry>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		let graph = read_graph(input)?;
+		Ok(compact_graph(&graph, |_, flow| flow == 0))
+	}
+
+	fn part_1(graph: &Self::Parsed) -> Result<Answer> {
+		Ok(maximum_flow(graph, 30)?.into())
+	}
+
+	fn part_2(graph: &Self::Parsed) -> Result<Answer> {
+		let cancel = CancellationToken::current();
+		let n = graph.len();
+		let mut flow = None;
+		for me in (1..n).combinations(n / 2) {
+			cancel.check()?;
+			let my_graph = compact_graph(graph, |node, _| me.contains(&node));
+			let elephants_graph = compact_graph(graph, |node, _| !me.contains(&node));
+			let combined = maximum_flow(&my_graph, 26)? + maximum_flow(&elephants_graph, 26)?;
+			flow = flow.max(Some(combined));
+		}
+		let flow = flow.ok_or_else(|| eyre!("no way to split the valves"))?;
+		Ok(flow.into())
+	}
+
+	fn lint(input: &str) -> Result<Vec<Check>> {
+		let valves = read_valves(input)?;
+		let mut checks = check_valves(&valves);
+		checks.extend(check_flows(&valves));
+		Ok(checks)
+	}
+
+	/// Generates `size` valves connected both ways, at most 15 of them with flow like in the actual
+	/// inputs, since part 2 tries every way to split those between you and the elephant.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let count = size.clamp(2, 26 * 26);
+		let mut names: Vec<String> = (b'A'..=b'Z')
+			.cartesian_product(b'A'..=b'Z')
+			.map(|(first, second)| String::from_utf8_lossy(&[first, second]).into_owned())
+			.filter(|name| name != "AA")
+			.collect();
+		names.shuffle(rng);
+		names.truncate(count - 1);
+		names.insert(0, "AA".to_string());
+
+		let mut flows = vec![0; count];
+		let with_flow = (count / 4).clamp(1, 15);
+		for valve in rand::seq::index::sample(rng, count - 1, with_flow) {
+			flows[valve + 1] = rng.gen_range(1..=25);
+		}
+
+		// every valve connects to one of the few before it, which makes long tunnels like in the
+		// actual inputs, and a few more tunnels make loops
+		let mut tunnels = vec![Vec::new(); count];
+		let mut connect = |a: usize, b: usize| {
+			if a != b && !tunnels[a].contains(&b) {
+				tunnels[a].push(b);
+				tunnels[b].push(a);
+			}
+		};
+		for valve in 1..count {
+			connect(valve, rng.gen_range(valve.saturating_sub(4)..valve));
+		}
+		for _ in 0..count / 10 {
+			connect(rng.gen_range(0..count), rng.gen_range(0..count));
+		}
+
+		let mut valves: Vec<String> = (0..count)
+			.map(|valve| {
+				let neighbors = tunnels[valve].iter().map(|&other| &names[other]).join(", ");
+				let tunnels = if tunnels[valve].len() == 1 {
+					"tunnel leads to valve"
+				} else {
+					"tunnels lead to valves"
+				};
+				format!(
+					"Valve {} has flow rate={}; {tunnels} {neighbors}\n",
+					names[valve], flows[valve]
+				)
+			})
+			.collect();
+		valves.shuffle(rng);
+		Ok(valves.concat())
+	}
+}
+
+solution!(Day16, day = 16, title = "Proboscidea Volcanium");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day16::solve(EXAMPLE)?, Answers::new(1651, 1707));
+		Ok(())
+	}
+
+	#[test]
+	fn every_failed_assumption_is_reported() -> Result<()> {
+		let failed = |input: &str| -> Result<Vec<_>> {
+			let checks = Day16::lint(input)?;
+			Ok(checks
+				.into_iter()
+				.filter(|check| check.result.is_err())
+				.map(|check| check.name)
+				.collect())
+		};
+		assert!(failed(EXAMPLE)?.is_empty());
+		let broken = EXAMPLE
+			.replace("AA has flow rate=0", "AA has flow rate=1")
+			.replace("valves CC, AA, EE", "valves CC, AA, EE, KK")
+			.replace("valves EE, GG", "valves GG");
+		assert_eq!(
+			failed(&broken)?,
+			[
+				"every valve is described once",
+				"every tunnel connects two valves both ways",
+				"valve AA has no flow",
+			]
+		);
+		assert!(Day16::parse(&broken).is_err());
+		Ok(())
+	}
+}
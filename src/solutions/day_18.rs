@@ -1,82 +1,149 @@
-use std::collections::HashSet;
-
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use itertools::Itertools;
-use linkme::distributed_slice;
-use ndarray::prelude::*;
-
-fn solution() -> Result<()> {
-	let mut surfaces: [_; 3] = array_init::array_init(|_| HashSet::new());
-	let mut voxels = Vec::new();
-	try_for_each_line_in_file("inputs/day-18", |line| {
-		let line = line.trim();
-		let pos: [_; 3] = array_init::from_iter(line.split(','))
-			.ok_or_else(|| eyre!("incorrect number of commas"))?;
-		let pos: [u8; 3] = array_init::try_array_init(|i| pos[i].parse())?;
-		voxels.push(pos);
-		for axis in 0..3 {
-			let surfaces = &mut surfaces[axis];
-			let mut side = pos;
-			if !surfaces.insert(side) {
-				surfaces.remove(&side);
-			}
-			side[axis] += 1;
-			if !surfaces.insert(side) {
-				surfaces.remove(&side);
-			}
-		}
-		Ok(())
-	})?;
-	let surface_area = surfaces.iter().map(HashSet::len).sum::<usize>();
-	println!("{surface_area}");
-
-	let shape = [0, 1, 2].map(|i| (voxels.iter().map(|p| p[i]).max().unwrap() + 3) as usize);
-	let mut map = Array3::from_elem(shape, 0i8);
-	voxels.into_iter().for_each(|voxel| {
-		let voxel = voxel.map(|i| (i + 1) as usize);
-		map[voxel] = 1;
-	});
-
-	let mut queue = vec![[0usize; 3]];
-	while let Some(voxel) = queue.pop() {
-		map[voxel] = -1;
-		(0..3)
-			.cartesian_product([-1, 1])
-			.for_each(|(axis, offset)| {
-				let mut voxel = voxel;
-				match voxel[axis].checked_add_signed(offset) {
-					Some(i) if i < map.raw_dim()[axis] => voxel[axis] = i,
-					_ => return,
-				};
-
-				if map[voxel] == 0 {
-					queue.push(voxel);
-				}
-			});
-	}
-
-	let interior_surface_area = map
-		.indexed_iter()
-		.map(|(voxel, value)| match value {
-			0 => (0..3)
-				.cartesian_product([-1, 1])
-				.filter(|&(axis, offset)| {
-					let mut voxel = [voxel.0, voxel.1, voxel.2];
-					voxel[axis] = voxel[axis].checked_add_signed(offset).unwrap();
-					map[voxel] != 0
-				})
-				.count(),
-			_ => 0,
-		})
-		.sum::<usize>();
-	let exterior_surface_area = surface_area - interior_surface_area;
-	println!("{exterior_surface_area}");
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_18: (usize, fn() -> Result<()>) = (18, solution);
+use std::collections::HashSet;
+
+use crate::generate::InputRng;
+use crate::geometry::{grid_neighbors, Bounds, Point};
+use crate::try_for_each_line;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+fn read_voxels(input: &str) -> Result<Vec<Point<3>>> {
+	let mut voxels = Vec::new();
+	try_for_each_line(input, |line| {
+		let line = line.trim();
+		let pos: [_; 3] = array_init::from_iter(line.split(','))
+			.ok_or_else(|| eyre!("incorrect number of commas"))?;
+		let pos: [i64; 3] = array_init::try_array_init(|i| pos[i].parse())?;
+		voxels.push(Point(pos));
+		Ok(())
+	})?;
+	Ok(voxels)
+}
+
+fn surface_area(voxels: &[Point<3>]) -> usize {
+	let mut surfaces: [_; 3] = array_init::array_init(|_| HashSet::new());
+	for &pos in voxels {
+		for axis in 0..3 {
+			let surfaces = &mut surfaces[axis];
+			let mut side = pos;
+			if !surfaces.insert(side) {
+				surfaces.remove(&side);
+			}
+			side[axis] += 1;
+			if !surfaces.insert(side) {
+				surfaces.remove(&side);
+			}
+		}
+	}
+	surfaces.iter().map(HashSet::len).sum::<usize>()
+}
+
+fn exterior_surface_area(voxels: &[Point<3>]) -> usize {
+	let surface_area = surface_area(voxels);
+
+	// with a layer of air all around, so that the air outside is connected
+	let Some(mut bounds) = Bounds::from_points(voxels.iter().copied()) else {
+		return 0;
+	};
+	bounds.extend(bounds.min - Point([1; 3]));
+	bounds.extend(bounds.max + Point([1; 3]));
+	let shape = bounds.size();
+	let mut map = Array3::from_elem(shape, 0i8);
+	voxels.iter().for_each(|&voxel| {
+		map[bounds.index(voxel).expect("within the bounds")] = 1;
+	});
+
+	let mut queue = vec![[0usize; 3]];
+	while let Some(voxel) = queue.pop() {
+		map[voxel] = -1;
+		queue.extend(grid_neighbors(voxel, shape).filter(|&neighbor| map[neighbor] == 0));
+	}
+
+	let interior_surface_area = map
+		.indexed_iter()
+		.map(|((i, j, k), value)| match value {
+			0 => grid_neighbors([i, j, k], shape)
+				.filter(|&neighbor| map[neighbor] != 0)
+				.count(),
+			_ => 0,
+		})
+		.sum::<usize>();
+	surface_area - interior_surface_area
+}
+
+struct Day18;
+
+impl Puzzle for Day18 {
+	type Parsed = Vec<Point<3>>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_voxels(input)
+	}
+
+	fn part_1(voxels: &Self::Parsed) -> Result<Answer> {
+		Ok(surface_area(voxels).into())
+	}
+
+	fn part_2(voxels: &Self::Parsed) -> Result<Answer> {
+		Ok(exterior_surface_area(voxels).into())
+	}
+
+	/// Generates a droplet of `size` cubes grown from a single one, which leaves some air pockets
+	/// inside.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let size = size.clamp(1, 20 * 20 * 20);
+		let mut cubes = vec![Point([10; 3])];
+		let mut seen = HashSet::from([Point([10; 3])]);
+		while cubes.len() < size {
+			let mut cube = *cubes.choose(rng).expect("not empty");
+			cube[rng.gen_range(0..3)] += if rng.gen_bool(0.5) { 1 } else { -1 };
+			if cube.0.iter().all(|&c| (0..20).contains(&c)) && seen.insert(cube) {
+				cubes.push(cube);
+			}
+		}
+		cubes.shuffle(rng);
+		Ok(cubes
+			.into_iter()
+			.map(|Point([x, y, z])| format!("{x},{y},{z}\n"))
+			.collect())
+	}
+}
+
+solution!(Day18, day = 18, title = "Boiling Boulders");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day18::solve(EXAMPLE)?, Answers::new(64, 58));
+		Ok(())
+	}
+
+	#[test]
+	fn small_example() -> Result<()> {
+		assert_eq!(surface_area(&read_voxels("1,1,1\n2,1,1\n")?), 10);
+		Ok(())
+	}
+}
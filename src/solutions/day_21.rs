@@ -1,411 +1,692 @@
-use std::{
-	collections::{hash_map::Entry, HashMap},
-	fmt::{Debug, Display, Write},
-	str::from_utf8,
-};
-
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-use regex::{Captures, Regex};
-
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct MonkeyName([u8; 4]);
-
-impl Debug for MonkeyName {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.write_fmt(format_args!("MonkeyName({})", from_utf8(&self.0).unwrap()))
-	}
-}
-
-impl Display for MonkeyName {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.write_str(from_utf8(&self.0).unwrap())
-	}
-}
-
-#[derive(Clone, Copy, Debug)]
-enum MonkeyBusiness<T> {
-	Yell(T),
-	Add(MonkeyName, MonkeyName),
-	Subtract(MonkeyName, MonkeyName),
-	Multiply(MonkeyName, MonkeyName),
-	Divide(MonkeyName, MonkeyName),
-}
-
-#[derive(Clone, Copy, Debug)]
-struct Monkey<T> {
-	name: MonkeyName,
-	business: MonkeyBusiness<T>,
-}
-
-fn read_input() -> Result<Vec<Monkey<i64>>> {
-	let [p0, p1] = [
-		r"^([a-z]{4}): (\d+)$",
-		r"^([a-z]{4}): ([a-z]{4}) ([+\-*/]) ([a-z]{4})$",
-	]
-	.map(Regex::new);
-	let patterns = [p0?, p1?];
-
-	let name_from_captures = |captures: &Captures, i: usize| {
-		MonkeyName(
-			array_init::from_iter(captures.get(i).unwrap().as_str().as_bytes().iter().copied())
-				.unwrap(),
-		)
-	};
-
-	let mut monkeys = vec![];
-	try_for_each_line_in_file("inputs/day-21", |line| {
-		let line = line.trim();
-		let monkey;
-		if let Some(captures) = patterns[0].captures(line) {
-			let name = name_from_captures(&captures, 1);
-			let value = captures.get(2).unwrap().as_str().parse()?;
-			let business = MonkeyBusiness::Yell(value);
-			monkey = Monkey { name, business }
-		} else if let Some(captures) = patterns[1].captures(line) {
-			let name = name_from_captures(&captures, 1);
-			let left = name_from_captures(&captures, 2);
-			let right = name_from_captures(&captures, 4);
-			let business_type = match captures.get(3).unwrap().as_str() {
-				"+" => MonkeyBusiness::Add,
-				"-" => MonkeyBusiness::Subtract,
-				"*" => MonkeyBusiness::Multiply,
-				"/" => MonkeyBusiness::Divide,
-				_ => unreachable!(),
-			};
-			let business = business_type(left, right);
-			monkey = Monkey { name, business };
-		} else {
-			return Err(eyre!("invalid input {line}"));
-		}
-		monkeys.push(monkey);
-		Ok(())
-	})?;
-
-	Ok(monkeys)
-}
-
-fn perform_monkey_business<T>(
-	name: MonkeyName,
-	monkeys: &mut HashMap<MonkeyName, MonkeyBusiness<T>>,
-) -> Result<T>
-where
-	T: Clone
-		+ std::ops::Add<T, Output = T>
-		+ std::ops::Sub<T, Output = T>
-		+ std::ops::Mul<T, Output = T>
-		+ std::ops::Div<T, Output = T>,
-{
-	let business_entry = match monkeys.entry(name) {
-		Entry::Occupied(business) => business,
-		_ => return Err(eyre!("invalid monkey {name} or cycle!")),
-	};
-
-	let (left, right) = match business_entry.get() {
-		MonkeyBusiness::Yell(value) => return Ok(value.clone()),
-		MonkeyBusiness::Add(left, right)
-		| MonkeyBusiness::Subtract(left, right)
-		| MonkeyBusiness::Multiply(left, right)
-		| MonkeyBusiness::Divide(left, right) => (*left, *right),
-	};
-
-	let business = business_entry.remove();
-	let left = perform_monkey_business(left, monkeys)?;
-	let right = perform_monkey_business(right, monkeys)?;
-
-	let result = match business {
-		MonkeyBusiness::Add(_, _) => left + right,
-		MonkeyBusiness::Subtract(_, _) => left - right,
-		MonkeyBusiness::Multiply(_, _) => left * right,
-		MonkeyBusiness::Divide(_, _) => left / right,
-		_ => unreachable!(),
-	};
-
-	monkeys.insert(name, MonkeyBusiness::Yell(result.clone()));
-
-	Ok(result)
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-enum Symbolic {
-	Unknown,
-	Constant(i64),
-	Add(Box<Symbolic>, Box<Symbolic>),
-	Subtract(Box<Symbolic>, Box<Symbolic>),
-	Multiply(Box<Symbolic>, Box<Symbolic>),
-	Divide(Box<Symbolic>, Box<Symbolic>),
-}
-
-impl Symbolic {
-	fn is_zero(&self) -> bool {
-		matches!(self, Symbolic::Constant(0))
-	}
-
-	fn is_one(&self) -> bool {
-		matches!(self, Symbolic::Constant(1))
-	}
-
-	fn constant(&self) -> Option<i64> {
-		match self {
-			Symbolic::Constant(constant) => Some(*constant),
-			_ => None,
-		}
-	}
-
-	fn simplify(self) -> Symbolic {
-		use Symbolic::*;
-		match self {
-			Add(left, right) => {
-				let left = left.simplify();
-				let right = right.simplify();
-				if left.is_zero() {
-					return right;
-				} else if right.is_zero() {
-					return left;
-				}
-				match (left, right) {
-					(Unknown, Unknown) => unreachable!(),
-					(Unknown, other) | (other, Unknown) => Add(Box::new(other), Box::new(Unknown)),
-					(Constant(left), Constant(right)) => Constant(left + right),
-					(Constant(constant), Add(left, right))
-					| (Add(left, right), Constant(constant)) => {
-						if let Some(other) = left.constant() {
-							let constant = constant + other;
-							if constant == 0 {
-								*right
-							} else {
-								Add(Box::new(Constant(constant)), right)
-							}
-						} else {
-							Add(Box::new(Constant(constant)), Box::new(Add(left, right)))
-						}
-					}
-					(Constant(constant), other) | (other, Constant(constant)) => {
-						Add(Box::new(Constant(constant)), Box::new(other))
-					}
-					(Subtract(a, b), Subtract(c, d)) => {
-						Subtract(Box::new(Add(a, c)), Box::new(Add(b, d)))
-					}
-					(left, right) => Add(Box::new(left), Box::new(right)),
-				}
-			}
-			Subtract(left, right) => {
-				let left = left.simplify();
-				let right = right.simplify();
-				if right.is_zero() {
-					return left;
-				}
-				match (left, right) {
-					(Unknown, Unknown) => unreachable!(),
-					(Constant(left), Constant(right)) => Constant(left - right),
-					(left, right) => Subtract(Box::new(left), Box::new(right)),
-				}
-			}
-			Multiply(left, right) => {
-				let left = left.simplify();
-				let right = right.simplify();
-				if left.is_one() {
-					return right;
-				} else if right.is_one() {
-					return left;
-				}
-				match (left, right) {
-					(Unknown, Unknown) => unreachable!(),
-					(Unknown, other) | (other, Unknown) => {
-						Multiply(Box::new(other), Box::new(Unknown))
-					}
-					(Constant(left), Constant(right)) => Constant(left * right),
-					(Constant(constant), Multiply(left, right))
-					| (Multiply(left, right), Constant(constant)) => {
-						if let Some(other) = left.constant() {
-							let constant = constant * other;
-							if constant == 1 {
-								*right
-							} else {
-								Multiply(Box::new(Constant(constant)), right)
-							}
-						} else {
-							Multiply(
-								Box::new(Constant(constant)),
-								Box::new(Multiply(left, right)),
-							)
-						}
-					}
-					(Constant(constant), other) | (other, Constant(constant)) => {
-						Multiply(Box::new(Constant(constant)), Box::new(other))
-					}
-					(Divide(a, b), Divide(c, d)) => {
-						Divide(Box::new(Multiply(a, c)), Box::new(Multiply(b, d)))
-					}
-					(left, right) => Multiply(Box::new(left), Box::new(right)),
-				}
-			}
-			Divide(left, right) => {
-				let left = left.simplify();
-				let right = right.simplify();
-				if right.is_one() {
-					return left;
-				}
-				match (left, right) {
-					(Unknown, Unknown) => unreachable!(),
-					(Constant(left), Constant(right)) => {
-						assert!(left.rem_euclid(right) == 0);
-						Constant(left / right)
-					}
-					(left, right) => Divide(Box::new(left), Box::new(right)),
-				}
-			}
-			simple => simple,
-		}
-	}
-
-	fn simplify_full(self) -> Symbolic {
-		let mut current = self;
-		let mut prev;
-		while {
-			prev = current.clone();
-			current = current.simplify();
-			prev != current
-		} {}
-		current
-	}
-}
-
-impl Display for Symbolic {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		use Symbolic::*;
-		match self {
-			Unknown => f.write_char('x'),
-			Constant(constant) => f.write_fmt(format_args!("{constant}")),
-			Add(left, right) => f.write_fmt(format_args!("({left} + {right})")),
-			Subtract(left, right) => f.write_fmt(format_args!("({left} - {right})")),
-			Multiply(left, right) => f.write_fmt(format_args!("({left} * {right})")),
-			Divide(left, right) => f.write_fmt(format_args!("({left} / {right})")),
-		}
-	}
-}
-
-impl std::ops::Add<Symbolic> for Symbolic {
-	type Output = Self;
-
-	fn add(self, rhs: Symbolic) -> Self::Output {
-		Symbolic::Add(Box::new(self), Box::new(rhs))
-	}
-}
-
-impl std::ops::Sub<Symbolic> for Symbolic {
-	type Output = Self;
-
-	fn sub(self, rhs: Symbolic) -> Self::Output {
-		Symbolic::Subtract(Box::new(self), Box::new(rhs))
-	}
-}
-
-impl std::ops::Mul<Symbolic> for Symbolic {
-	type Output = Self;
-
-	fn mul(self, rhs: Symbolic) -> Self::Output {
-		Symbolic::Multiply(Box::new(self), Box::new(rhs))
-	}
-}
-
-impl std::ops::Div<Symbolic> for Symbolic {
-	type Output = Self;
-
-	fn div(self, rhs: Symbolic) -> Self::Output {
-		Symbolic::Divide(Box::new(self), Box::new(rhs))
-	}
-}
-
-fn solution() -> Result<()> {
-	let monkeys = read_input()?;
-
-	let mut memoized_monkeys =
-		HashMap::<_, _>::from_iter(monkeys.iter().copied().map(|m| (m.name, m.business)));
-
-	let mut symbolic_monkeys = HashMap::<_, _>::from_iter(memoized_monkeys.iter().map(|(k, v)| {
-		let k = *k;
-		let v = if k == MonkeyName(*b"humn") {
-			MonkeyBusiness::Yell(Symbolic::Unknown)
-		} else {
-			match *v {
-				MonkeyBusiness::Yell(v) => MonkeyBusiness::Yell(Symbolic::Constant(v)),
-				MonkeyBusiness::Add(left, right) => MonkeyBusiness::Add(left, right),
-				MonkeyBusiness::Subtract(left, right) => MonkeyBusiness::Subtract(left, right),
-				MonkeyBusiness::Multiply(left, right) => MonkeyBusiness::Multiply(left, right),
-				MonkeyBusiness::Divide(left, right) => MonkeyBusiness::Divide(left, right),
-			}
-		};
-		(k, v)
-	}));
-
-	let result = perform_monkey_business(MonkeyName(*b"root"), &mut memoized_monkeys)?;
-	println!("{result}");
-
-	let root = symbolic_monkeys.remove(&MonkeyName(*b"root")).unwrap();
-	let (left, right) = match root {
-		MonkeyBusiness::Yell(_) => return Err(eyre!("root doesn't have dependents!")),
-		MonkeyBusiness::Add(left, right)
-		| MonkeyBusiness::Subtract(left, right)
-		| MonkeyBusiness::Multiply(left, right)
-		| MonkeyBusiness::Divide(left, right) => (left, right),
-	};
-
-	let left = perform_monkey_business(left, &mut symbolic_monkeys)?.simplify_full();
-	let right = perform_monkey_business(right, &mut symbolic_monkeys)?.simplify_full();
-
-	let (target, mut expression) = match (left, right) {
-		(Symbolic::Constant(constant), right) => (constant, right),
-		(left, Symbolic::Constant(constant)) => (constant, left),
-		_ => return Err(eyre!("expected one side to be a constant")),
-	};
-
-	let mut target = num::Rational64::from_integer(target);
-	while !matches!(expression, Symbolic::Unknown) {
-		match expression {
-			Symbolic::Unknown | Symbolic::Constant(_) => unreachable!(),
-			Symbolic::Add(constant, other) => {
-				let constant = constant.constant().expect("not simplified properly?");
-				target -= constant;
-				expression = *other;
-			}
-			Symbolic::Subtract(left, right) => match (left.as_ref(), right.as_ref()) {
-				(Symbolic::Constant(constant), _) => {
-					target = num::Rational64::from_integer(*constant) - target;
-					expression = *right;
-				}
-				(_, Symbolic::Constant(constant)) => {
-					target += *constant;
-					expression = *left;
-				}
-				_ => unreachable!("not simplified properly?"),
-			},
-			Symbolic::Multiply(constant, other) => {
-				let constant = constant.constant().expect("not simplified properly?");
-				target /= constant;
-				expression = *other;
-			}
-			Symbolic::Divide(left, right) => match (left.as_ref(), right.as_ref()) {
-				(Symbolic::Constant(constant), _) => {
-					target = num::Rational64::from_integer(*constant) / target;
-					expression = *right;
-				}
-				(_, Symbolic::Constant(constant)) => {
-					target *= *constant;
-					expression = *left;
-				}
-				_ => unreachable!("not simplified properly?"),
-			},
-		}
-	}
-
-	println!("{target}");
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_21: (usize, fn() -> Result<()>) = (21, solution);
+use std::{
+	collections::{hash_map::Entry, HashMap, HashSet},
+	fmt::{Debug, Display, Write},
+};
+
+use crate::generate::InputRng;
+use crate::lint::{self, Check};
+use crate::{capture, try_for_each_line};
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use num::traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
+use once_cell::sync::Lazy;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::{Captures, Regex};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct MonkeyName([u8; 4]);
+
+impl Debug for MonkeyName {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "MonkeyName({self})")
+	}
+}
+
+impl Display for MonkeyName {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&String::from_utf8_lossy(&self.0))
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+enum MonkeyBusiness<T> {
+	Yell(T),
+	Add(MonkeyName, MonkeyName),
+	Subtract(MonkeyName, MonkeyName),
+	Multiply(MonkeyName, MonkeyName),
+	Divide(MonkeyName, MonkeyName),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Monkey<T> {
+	name: MonkeyName,
+	business: MonkeyBusiness<T>,
+}
+
+static PATTERNS: Lazy<[Regex; 2]> = Lazy::new(|| {
+	[
+		r"^([a-z]{4}): (\d+)$",
+		r"^([a-z]{4}): ([a-z]{4}) ([+\-*/]) ([a-z]{4})$",
+	]
+	.map(|pattern| Regex::new(pattern).expect("valid pattern"))
+});
+
+fn read_input(input: &str) -> Result<Vec<Monkey<i64>>> {
+	let name_from_captures = |captures: &Captures, i: usize| -> Result<MonkeyName> {
+		let name = capture(captures, i)?.as_bytes().try_into()?;
+		Ok(MonkeyName(name))
+	};
+
+	let mut monkeys = vec![];
+	try_for_each_line(input, |line| {
+		let line = line.trim();
+		let monkey;
+		if let Some(captures) = PATTERNS[0].captures(line) {
+			let name = name_from_captures(&captures, 1)?;
+			let value = capture(&captures, 2)?.parse()?;
+			let business = MonkeyBusiness::Yell(value);
+			monkey = Monkey { name, business }
+		} else if let Some(captures) = PATTERNS[1].captures(line) {
+			let name = name_from_captures(&captures, 1)?;
+			let left = name_from_captures(&captures, 2)?;
+			let right = name_from_captures(&captures, 4)?;
+			let business_type = match capture(&captures, 3)? {
+				"+" => MonkeyBusiness::Add,
+				"-" => MonkeyBusiness::Subtract,
+				"*" => MonkeyBusiness::Multiply,
+				"/" => MonkeyBusiness::Divide,
+				operation => return Err(eyre!("unknown operation {operation}")),
+			};
+			let business = business_type(left, right);
+			monkey = Monkey { name, business };
+		} else {
+			return Err(eyre!("invalid input {line}"));
+		}
+		monkeys.push(monkey);
+		Ok(())
+	})?;
+
+	Ok(monkeys)
+}
+
+/// What the monkeys do with the numbers they hear. Fails instead of overflowing or dividing by
+/// zero.
+trait Arithmetic: Sized {
+	fn add(self, other: Self) -> Result<Self>;
+	fn subtract(self, other: Self) -> Result<Self>;
+	fn multiply(self, other: Self) -> Result<Self>;
+	fn divide(self, other: Self) -> Result<Self>;
+}
+
+impl Arithmetic for i64 {
+	fn add(self, other: i64) -> Result<i64> {
+		self.checked_add(other)
+			.ok_or_else(|| eyre!("{self} + {other} overflows"))
+	}
+
+	fn subtract(self, other: i64) -> Result<i64> {
+		self.checked_sub(other)
+			.ok_or_else(|| eyre!("{self} - {other} overflows"))
+	}
+
+	fn multiply(self, other: i64) -> Result<i64> {
+		self.checked_mul(other)
+			.ok_or_else(|| eyre!("{self} * {other} overflows"))
+	}
+
+	fn divide(self, other: i64) -> Result<i64> {
+		self.checked_div(other)
+			.ok_or_else(|| eyre!("{self} / {other} divides by zero or overflows"))
+	}
+}
+
+fn perform_monkey_business<T: Clone + Arithmetic>(
+	name: MonkeyName,
+	monkeys: &mut HashMap<MonkeyName, MonkeyBusiness<T>>,
+) -> Result<T> {
+	let business_entry = match monkeys.entry(name) {
+		Entry::Occupied(business) => business,
+		_ => return Err(eyre!("invalid monkey {name} or cycle!")),
+	};
+
+	let (left, right) = match business_entry.get() {
+		MonkeyBusiness::Yell(value) => return Ok(value.clone()),
+		MonkeyBusiness::Add(left, right)
+		| MonkeyBusiness::Subtract(left, right)
+		| MonkeyBusiness::Multiply(left, right)
+		| MonkeyBusiness::Divide(left, right) => (*left, *right),
+	};
+
+	let business = business_entry.remove();
+	let left = perform_monkey_business(left, monkeys)?;
+	let right = perform_monkey_business(right, monkeys)?;
+
+	let result = match business {
+		MonkeyBusiness::Add(_, _) => left.add(right)?,
+		MonkeyBusiness::Subtract(_, _) => left.subtract(right)?,
+		MonkeyBusiness::Multiply(_, _) => left.multiply(right)?,
+		MonkeyBusiness::Divide(_, _) => left.divide(right)?,
+		_ => unreachable!(),
+	};
+
+	monkeys.insert(name, MonkeyBusiness::Yell(result.clone()));
+
+	Ok(result)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Symbolic {
+	Unknown,
+	Constant(i64),
+	Add(Box<Symbolic>, Box<Symbolic>),
+	Subtract(Box<Symbolic>, Box<Symbolic>),
+	Multiply(Box<Symbolic>, Box<Symbolic>),
+	Divide(Box<Symbolic>, Box<Symbolic>),
+}
+
+impl Symbolic {
+	fn is_zero(&self) -> bool {
+		matches!(self, Symbolic::Constant(0))
+	}
+
+	fn is_one(&self) -> bool {
+		matches!(self, Symbolic::Constant(1))
+	}
+
+	fn constant(&self) -> Option<i64> {
+		match self {
+			Symbolic::Constant(constant) => Some(*constant),
+			_ => None,
+		}
+	}
+
+	fn simplify(self) -> Result<Symbolic> {
+		use Symbolic::*;
+		Ok(match self {
+			Add(left, right) => {
+				let left = left.simplify()?;
+				let right = right.simplify()?;
+				if left.is_zero() {
+					return Ok(right);
+				} else if right.is_zero() {
+					return Ok(left);
+				}
+				match (left, right) {
+					(Unknown, Unknown) => unreachable!(),
+					(Unknown, other) | (other, Unknown) => Add(Box::new(other), Box::new(Unknown)),
+					(Constant(left), Constant(right)) => Constant(left.add(right)?),
+					(Constant(constant), Add(left, right))
+					| (Add(left, right), Constant(constant)) => {
+						if let Some(other) = left.constant() {
+							let constant = constant.add(other)?;
+							if constant == 0 {
+								*right
+							} else {
+								Add(Box::new(Constant(constant)), right)
+							}
+						} else {
+							Add(Box::new(Constant(constant)), Box::new(Add(left, right)))
+						}
+					}
+					(Constant(constant), other) | (other, Constant(constant)) => {
+						Add(Box::new(Constant(constant)), Box::new(other))
+					}
+					(Subtract(a, b), Subtract(c, d)) => {
+						Subtract(Box::new(Add(a, c)), Box::new(Add(b, d)))
+					}
+					(left, right) => Add(Box::new(left), Box::new(right)),
+				}
+			}
+			Subtract(left, right) => {
+				let left = left.simplify()?;
+				let right = right.simplify()?;
+				if right.is_zero() {
+					return Ok(left);
+				}
+				match (left, right) {
+					(Unknown, Unknown) => unreachable!(),
+					(Constant(left), Constant(right)) => Constant(left.subtract(right)?),
+					(left, right) => Subtract(Box::new(left), Box::new(right)),
+				}
+			}
+			Multiply(left, right) => {
+				let left = left.simplify()?;
+				let right = right.simplify()?;
+				if left.is_one() {
+					return Ok(right);
+				} else if right.is_one() {
+					return Ok(left);
+				}
+				match (left, right) {
+					(Unknown, Unknown) => unreachable!(),
+					(Unknown, other) | (other, Unknown) => {
+						Multiply(Box::new(other), Box::new(Unknown))
+					}
+					(Constant(left), Constant(right)) => Constant(left.multiply(right)?),
+					(Constant(constant), Multiply(left, right))
+					| (Multiply(left, right), Constant(constant)) => {
+						if let Some(other) = left.constant() {
+							let constant = constant.multiply(other)?;
+							if constant == 1 {
+								*right
+							} else {
+								Multiply(Box::new(Constant(constant)), right)
+							}
+						} else {
+							Multiply(
+								Box::new(Constant(constant)),
+								Box::new(Multiply(left, right)),
+							)
+						}
+					}
+					(Constant(constant), other) | (other, Constant(constant)) => {
+						Multiply(Box::new(Constant(constant)), Box::new(other))
+					}
+					(Divide(a, b), Divide(c, d)) => {
+						Divide(Box::new(Multiply(a, c)), Box::new(Multiply(b, d)))
+					}
+					(left, right) => Multiply(Box::new(left), Box::new(right)),
+				}
+			}
+			Divide(left, right) => {
+				let left = left.simplify()?;
+				let right = right.simplify()?;
+				if right.is_one() {
+					return Ok(left);
+				}
+				match (left, right) {
+					(Unknown, Unknown) => unreachable!(),
+					(Constant(left), Constant(right)) => {
+						if right != 0 && left % right != 0 {
+							return Err(eyre!("{left} / {right} isn't a whole number"));
+						}
+						Constant(left.divide(right)?)
+					}
+					(left, right) => Divide(Box::new(left), Box::new(right)),
+				}
+			}
+			simple => simple,
+		})
+	}
+
+	fn simplify_full(self) -> Result<Symbolic> {
+		let mut current = self;
+		let mut prev;
+		while {
+			prev = current.clone();
+			current = current.simplify()?;
+			prev != current
+		} {}
+		Ok(current)
+	}
+}
+
+impl Display for Symbolic {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		use Symbolic::*;
+		match self {
+			Unknown => f.write_char('x'),
+			Constant(constant) => f.write_fmt(format_args!("{constant}")),
+			Add(left, right) => f.write_fmt(format_args!("({left} + {right})")),
+			Subtract(left, right) => f.write_fmt(format_args!("({left} - {right})")),
+			Multiply(left, right) => f.write_fmt(format_args!("({left} * {right})")),
+			Divide(left, right) => f.write_fmt(format_args!("({left} / {right})")),
+		}
+	}
+}
+
+impl Arithmetic for Symbolic {
+	fn add(self, other: Symbolic) -> Result<Symbolic> {
+		Ok(Symbolic::Add(Box::new(self), Box::new(other)))
+	}
+
+	fn subtract(self, other: Symbolic) -> Result<Symbolic> {
+		Ok(Symbolic::Subtract(Box::new(self), Box::new(other)))
+	}
+
+	fn multiply(self, other: Symbolic) -> Result<Symbolic> {
+		Ok(Symbolic::Multiply(Box::new(self), Box::new(other)))
+	}
+
+	fn divide(self, other: Symbolic) -> Result<Symbolic> {
+		Ok(Symbolic::Divide(Box::new(self), Box::new(other)))
+	}
+}
+
+fn root_number(monkeys: &[Monkey<i64>]) -> Result<i64> {
+	let mut memoized_monkeys =
+		HashMap::<_, _>::from_iter(monkeys.iter().copied().map(|m| (m.name, m.business)));
+
+	perform_monkey_business(MonkeyName(*b"root"), &mut memoized_monkeys)
+}
+
+fn human_number(monkeys: &[Monkey<i64>]) -> Result<Answer> {
+	let mut symbolic_monkeys = HashMap::<_, _>::from_iter(monkeys.iter().map(|m| {
+		let k = m.name;
+		let v = if k == MonkeyName(*b"humn") {
+			MonkeyBusiness::Yell(Symbolic::Unknown)
+		} else {
+			match m.business {
+				MonkeyBusiness::Yell(v) => MonkeyBusiness::Yell(Symbolic::Constant(v)),
+				MonkeyBusiness::Add(left, right) => MonkeyBusiness::Add(left, right),
+				MonkeyBusiness::Subtract(left, right) => MonkeyBusiness::Subtract(left, right),
+				MonkeyBusiness::Multiply(left, right) => MonkeyBusiness::Multiply(left, right),
+				MonkeyBusiness::Divide(left, right) => MonkeyBusiness::Divide(left, right),
+			}
+		};
+		(k, v)
+	}));
+
+	let root = symbolic_monkeys
+		.remove(&MonkeyName(*b"root"))
+		.ok_or_else(|| eyre!("there is no root monkey"))?;
+	let (left, right) = match root {
+		MonkeyBusiness::Yell(_) => return Err(eyre!("root doesn't have dependents!")),
+		MonkeyBusiness::Add(left, right)
+		| MonkeyBusiness::Subtract(left, right)
+		| MonkeyBusiness::Multiply(left, right)
+		| MonkeyBusiness::Divide(left, right) => (left, right),
+	};
+
+	let left = perform_monkey_business(left, &mut symbolic_monkeys)?.simplify_full()?;
+	let right = perform_monkey_business(right, &mut symbolic_monkeys)?.simplify_full()?;
+
+	let (target, mut expression) = match (left, right) {
+		(Symbolic::Constant(constant), right) => (constant, right),
+		(left, Symbolic::Constant(constant)) => (constant, left),
+		_ => return Err(eyre!("expected one side to be a constant")),
+	};
+
+	let overflow = || eyre!("the number for humn overflows or divides by zero");
+	let mut target = num::Rational64::from_integer(target);
+	while !matches!(expression, Symbolic::Unknown) {
+		match expression {
+			Symbolic::Unknown | Symbolic::Constant(_) => unreachable!(),
+			Symbolic::Add(constant, other) => {
+				let constant = constant.constant().expect("not simplified properly?");
+				target = target.checked_sub(&constant.into()).ok_or_else(overflow)?;
+				expression = *other;
+			}
+			Symbolic::Subtract(left, right) => match (left.as_ref(), right.as_ref()) {
+				(Symbolic::Constant(constant), _) => {
+					target = num::Rational64::from_integer(*constant)
+						.checked_sub(&target)
+						.ok_or_else(overflow)?;
+					expression = *right;
+				}
+				(_, Symbolic::Constant(constant)) => {
+					target = target
+						.checked_add(&(*constant).into())
+						.ok_or_else(overflow)?;
+					expression = *left;
+				}
+				_ => unreachable!("not simplified properly?"),
+			},
+			Symbolic::Multiply(constant, other) => {
+				let constant = constant.constant().expect("not simplified properly?");
+				target = target.checked_div(&constant.into()).ok_or_else(overflow)?;
+				expression = *other;
+			}
+			Symbolic::Divide(left, right) => match (left.as_ref(), right.as_ref()) {
+				(Symbolic::Constant(constant), _) => {
+					target = num::Rational64::from_integer(*constant)
+						.checked_div(&target)
+						.ok_or_else(overflow)?;
+					expression = *right;
+				}
+				(_, Symbolic::Constant(constant)) => {
+					target = target
+						.checked_mul(&(*constant).into())
+						.ok_or_else(overflow)?;
+					expression = *left;
+				}
+				_ => unreachable!("not simplified properly?"),
+			},
+		}
+	}
+
+	if target.is_integer() {
+		Ok(target.to_integer().into())
+	} else {
+		Ok(target.to_string().into())
+	}
+}
+
+/// How often `name` depends on `humn`, counting every way it does.
+fn humn_uses(
+	name: MonkeyName,
+	monkeys: &HashMap<MonkeyName, MonkeyBusiness<i64>>,
+	counted: &mut HashMap<MonkeyName, Option<usize>>,
+) -> Result<usize> {
+	if name == MonkeyName(*b"humn") {
+		return Ok(1);
+	}
+	match counted.get(&name) {
+		Some(Some(uses)) => return Ok(*uses),
+		Some(None) => return Err(eyre!("{name} depends on itself")),
+		None => {}
+	}
+	let uses = match monkeys.get(&name) {
+		None => return Err(eyre!("there is no monkey {name}")),
+		Some(MonkeyBusiness::Yell(_)) => 0,
+		Some(
+			MonkeyBusiness::Add(left, right)
+			| MonkeyBusiness::Subtract(left, right)
+			| MonkeyBusiness::Multiply(left, right)
+			| MonkeyBusiness::Divide(left, right),
+		) => {
+			// marks the monkey as in progress, to notice cycles
+			counted.insert(name, None);
+			humn_uses(*left, monkeys, counted)? + humn_uses(*right, monkeys, counted)?
+		}
+	};
+	counted.insert(name, Some(uses));
+	Ok(uses)
+}
+
+fn check_monkeys(monkeys: &[Monkey<i64>]) -> Vec<Check> {
+	let mut names = HashMap::new();
+	let unique = match monkeys
+		.iter()
+		.find(|monkey| names.insert(monkey.name, monkey.business).is_some())
+	{
+		Some(monkey) => Err(eyre!("{} yells more than once", monkey.name)),
+		None => Ok(()),
+	};
+
+	// part 2 solves for humn by undoing the operations on its side of root one by one
+	let humn = match names.get(&MonkeyName(*b"root")) {
+		None => Err(eyre!("there is no root monkey")),
+		Some(MonkeyBusiness::Yell(_)) => Err(eyre!("root just yells a number")),
+		Some(
+			MonkeyBusiness::Add(left, right)
+			| MonkeyBusiness::Subtract(left, right)
+			| MonkeyBusiness::Multiply(left, right)
+			| MonkeyBusiness::Divide(left, right),
+		) => {
+			let mut counted = HashMap::new();
+			let mut uses = |name| humn_uses(name, &names, &mut counted);
+			match (uses(*left), uses(*right)) {
+				(Ok(1), Ok(0)) | (Ok(0), Ok(1)) => Ok(()),
+				(Ok(left), Ok(right)) => Err(eyre!(
+					"humn is used {left} times on the left of root and {right} times on the right"
+				)),
+				(Err(error), _) | (_, Err(error)) => Err(error),
+			}
+		}
+	};
+
+	vec![
+		Check::new("every monkey yells once", unique),
+		Check::new("humn appears once, on one side of root", humn),
+	]
+}
+
+struct Day21;
+
+impl Puzzle for Day21 {
+	type Parsed = Vec<Monkey<i64>>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		let monkeys = read_input(input)?;
+		lint::require(check_monkeys(&monkeys))?;
+		Ok(monkeys)
+	}
+
+	fn part_1(monkeys: &Self::Parsed) -> Result<Answer> {
+		Ok(root_number(monkeys)?.into())
+	}
+
+	fn part_2(monkeys: &Self::Parsed) -> Result<Answer> {
+		human_number(monkeys)
+	}
+
+	fn lint(input: &str) -> Result<Vec<Check>> {
+		Ok(check_monkeys(&read_input(input)?))
+	}
+
+	/// Generates about `size` monkeys in a tree. Both sides of root yell the same number when humn
+	/// yells the answer to part 2, but humn yells some other number in the input.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		struct Generator<'a> {
+			rng: &'a mut InputRng,
+			names: HashSet<[u8; 4]>,
+			monkeys: Vec<String>,
+		}
+
+		impl Generator<'_> {
+			fn name(&mut self) -> String {
+				loop {
+					let name = array_init::array_init(|_| self.rng.gen_range(b'a'..=b'z'));
+					if ![*b"root", *b"humn"].contains(&name) && self.names.insert(name) {
+						return MonkeyName(name).to_string();
+					}
+				}
+			}
+
+			/// Adds the monkeys that yell `value`, with `leaves` of them yelling numbers. Only
+			/// additions, subtractions and multiplications by numbers other than zero lead to humn,
+			/// so that part 2 can undo them.
+			fn monkey(&mut self, value: i64, leaves: usize, humn: bool) -> String {
+				let rng = &mut *self.rng;
+				if leaves == 1 {
+					let (name, value) = if humn {
+						("humn".to_string(), rng.gen_range(0..=2 * value + 10))
+					} else {
+						(self.name(), value)
+					};
+					self.monkeys.push(format!("{name}: {value}\n"));
+					return name;
+				}
+
+				let (left, operation, right) = match rng.gen_range(0..if humn { 3 } else { 4 }) {
+					0 => {
+						let left = rng.gen_range(0..=value);
+						(left, '+', value - left)
+					}
+					1 => {
+						let right = rng.gen_range(1..=20);
+						(value + right, '-', right)
+					}
+					2 => {
+						let divisors: Vec<_> =
+							(1..=10).filter(|&divisor| value % divisor == 0).collect();
+						let divisor = *divisors.choose(rng).expect("one divides everything");
+						if rng.gen_bool(0.5) {
+							(value / divisor, '*', divisor)
+						} else {
+							(divisor, '*', value / divisor)
+						}
+					}
+					// keeps the numbers in check, since division multiplies them
+					_ if value > 1_000_000_000 => (value, '+', 0),
+					_ => {
+						let right = rng.gen_range(1..=5);
+						(value * right, '/', right)
+					}
+				};
+				// humn goes to a side that yells more than zero, so that nothing on its way to root
+				// multiplies by zero, and only an addition can have a side that yells zero there
+				let humn_left = match operation {
+					'+' => left >= right,
+					_ => rng.gen_bool(0.5),
+				};
+				let left_leaves = rng.gen_range(1..leaves);
+				let name = self.name();
+				let left = self.monkey(left, left_leaves, humn && humn_left);
+				let right = self.monkey(right, leaves - left_leaves, humn && !humn_left);
+				self.monkeys
+					.push(format!("{name}: {left} {operation} {right}\n"));
+				name
+			}
+		}
+
+		let mut generator = Generator {
+			rng,
+			names: HashSet::new(),
+			monkeys: Vec::new(),
+		};
+		let value = generator.rng.gen_range(1..=1_000_000_000);
+		let leaves = size.div_ceil(2).max(2);
+		let left_leaves = generator.rng.gen_range(1..leaves);
+		let humn_left = generator.rng.gen_bool(0.5);
+		let left = generator.monkey(value, left_leaves, humn_left);
+		let right = generator.monkey(value, leaves - left_leaves, !humn_left);
+		generator.monkeys.push(format!("root: {left} + {right}\n"));
+		generator.monkeys.shuffle(generator.rng);
+		Ok(generator.monkeys.concat())
+	}
+}
+
+solution!(Day21, day = 21, title = "Monkey Math");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day21::solve(EXAMPLE)?, Answers::new(152, 301));
+		Ok(())
+	}
+
+	#[test]
+	fn humn_is_checked() -> Result<()> {
+		let failed = |input: &str| -> Result<Vec<_>> {
+			let checks = Day21::lint(input)?;
+			Ok(checks
+				.into_iter()
+				.filter(|check| check.result.is_err())
+				.map(|check| check.name)
+				.collect())
+		};
+		assert!(failed(EXAMPLE)?.is_empty());
+		let twice = EXAMPLE.replace("dvpt: 3", "dvpt: humn + zczc");
+		assert_eq!(failed(&twice)?, ["humn appears once, on one side of root"]);
+		let cycle = EXAMPLE.replace("dvpt: 3", "dvpt: ptdq + zczc");
+		assert_eq!(failed(&cycle)?, ["humn appears once, on one side of root"]);
+		Ok(())
+	}
+
+	#[test]
+	fn bad_divisions_are_errors() -> Result<()> {
+		let by_zero = Day21::parse(&EXAMPLE.replace("lfqf: 4", "lfqf: 0"))?;
+		assert!(Day21::part_1(&by_zero).is_err());
+		let inexact = EXAMPLE.replace("drzm * dbpl", "drzm / dbpl");
+		let inexact = Day21::parse(&inexact.replace("hmdt: 32", "hmdt: 33"))?;
+		assert!(Day21::part_2(&inexact).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn root_is_required() -> Result<()> {
+		for input in ["", "abcd: 5\n"] {
+			assert!(Day21::parse(input).is_err());
+			let monkeys = read_input(input)?;
+			assert!(Day21::part_1(&monkeys).is_err());
+			assert!(Day21::part_2(&monkeys).is_err());
+		}
+		Ok(())
+	}
+}
@@ -1,400 +1,518 @@
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-use ndarray::{concatenate, prelude::*};
-use num::Integer;
-
-#[derive(Clone, Copy, Debug)]
-enum Instruction {
-	Forward(usize),
-	Clockwise,
-	Counterclockwise,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[repr(u8)]
-enum Tile {
-	Empty = b'.',
-	Wall = b'#',
-	Warp = b' ',
-}
-
-#[derive(Clone, Copy, Debug)]
-struct Turtle {
-	position: [usize; 2],
-	facing: [i8; 2],
-}
-
-impl Turtle {
-	fn new(map: ArrayView2<'_, Tile>) -> Result<Turtle> {
-		let start = map
-			.rows()
-			.into_iter()
-			.next()
-			.ok_or_else(|| eyre!("empty map!"))?
-			.into_iter()
-			.position(|t| matches!(t, Tile::Empty))
-			.ok_or_else(|| eyre!("no empty tiles in topmost row!"))?;
-
-		let turtle = Turtle {
-			position: [0, start],
-			facing: [0, 1],
-		};
-
-		Ok(turtle)
-	}
-
-	fn process_instruction(&mut self, instruction: Instruction, map: ArrayView2<'_, Tile>) {
-		match instruction {
-			Instruction::Forward(n) => {
-				for _ in 0..n {
-					let mut next_tile = self.position;
-					while {
-						next_tile
-							.iter_mut()
-							.zip(self.facing)
-							.zip(map.shape())
-							.for_each(|((x, v), d)| {
-								*x = (*x as isize + v as isize).rem_euclid(*d as isize) as usize;
-							});
-						map[next_tile] == Tile::Warp
-					} {}
-					if map[next_tile] != Tile::Empty {
-						break;
-					}
-					self.position = next_tile;
-				}
-			}
-			Instruction::Clockwise => {
-				self.facing = [self.facing[1], -self.facing[0]];
-			}
-			Instruction::Counterclockwise => {
-				self.facing = [-self.facing[1], self.facing[0]];
-			}
-		}
-	}
-
-	fn process_instruction_on_cube(
-		&mut self,
-		instruction: Instruction,
-		map: ArrayView2<'_, Tile>,
-		cube_map: [[FaceWithFrame; 2]; 3],
-	) {
-		match instruction {
-			Instruction::Forward(n) => {
-				for _ in 0..n {
-					let mut next_tile = self.position.map(|i| i as isize);
-					next_tile.iter_mut().zip(self.facing).for_each(|(x, v)| {
-						*x += v as isize;
-					});
-					let mut next_facing = self.facing;
-
-					if next_tile
-						.iter()
-						.zip(map.shape())
-						.any(|(&x, &d)| x < 0 || x as usize >= d)
-						|| map[next_tile.map(|i| i as usize)] == Tile::Warp
-					{
-						let current_face = self.position.map(|i| i / FACE_SIZE);
-						let (_, current_frame) = cube_map
-							.iter()
-							.copied()
-							.flatten()
-							.find(|f| f.0 == current_face)
-							.unwrap();
-						let mut expected_next_frame = match self.facing {
-							[0, 1] => current_frame.next_j(),
-							[0, -1] => current_frame.prev_j(),
-							[1, 0] => current_frame.next_i(),
-							[-1, 0] => current_frame.prev_i(),
-							_ => unreachable!(),
-						};
-						let (next_axis, next_negated) = expected_next_frame.to_cube_indices();
-						let (next_face, next_frame) = cube_map[next_axis][next_negated];
-						next_tile = next_tile.map(|e| e.rem_euclid(FACE_SIZE as isize));
-						if expected_next_frame.0[0].iter().position(|e| *e != 0)
-							!= next_frame.0[0].iter().position(|e| *e != 0)
-						{
-							expected_next_frame.0 = [
-								expected_next_frame.0[1],
-								expected_next_frame.0[0],
-								expected_next_frame.0[2],
-							];
-							next_tile = [next_tile[1], next_tile[0]];
-							next_facing = [next_facing[1], next_facing[0]];
-						}
-						for i in 0..2 {
-							if expected_next_frame.0[i] == next_frame.0[i] {
-								continue;
-							}
-							expected_next_frame.0[i] = expected_next_frame.0[i].map(|e| -e);
-							next_tile[i] = FACE_SIZE as isize - next_tile[i] - 1;
-							next_facing[i] = -next_facing[i];
-						}
-						assert_eq!(expected_next_frame, next_frame);
-						next_tile.iter_mut().zip(next_face).for_each(|(t, f)| {
-							*t += (FACE_SIZE * f) as isize;
-						});
-					}
-
-					let next_tile = next_tile.map(|i| i as usize);
-					assert_ne!(map[next_tile], Tile::Warp);
-					if map[next_tile] != Tile::Empty {
-						break;
-					}
-					self.position = next_tile;
-					self.facing = next_facing;
-				}
-			}
-			_ => self.process_instruction(instruction, map),
-		}
-	}
-
-	fn value(&self) -> usize {
-		1000 * (self.position[0] + 1)
-			+ 4 * (self.position[1] + 1)
-			+ match self.facing {
-				[0, 1] => 0,
-				[1, 0] => 1,
-				[0, -1] => 2,
-				[-1, 0] => 3,
-				_ => unreachable!(),
-			}
-	}
-}
-
-const FACE_SIZE: usize = 50;
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Frame([[i8; 3]; 3]);
-
-impl Frame {
-	fn new() -> Frame {
-		Frame([[1, 0, 0], [0, 1, 0], [0, 0, 1]])
-	}
-
-	fn next_i(self) -> Frame {
-		let map_i = self.0[2].map(|e| -e);
-		let map_j = self.0[1];
-		let normal = self.0[0];
-		Frame([map_i, map_j, normal])
-	}
-
-	fn prev_i(self) -> Frame {
-		let map_i = self.0[2];
-		let map_j = self.0[1];
-		let normal = self.0[0].map(|e| -e);
-		Frame([map_i, map_j, normal])
-	}
-
-	fn next_j(self) -> Frame {
-		let map_i = self.0[0];
-		let map_j = self.0[2].map(|e| -e);
-		let normal = self.0[1];
-		Frame([map_i, map_j, normal])
-	}
-
-	fn prev_j(self) -> Frame {
-		let map_i = self.0[0];
-		let map_j = self.0[2];
-		let normal = self.0[1].map(|e| -e);
-		Frame([map_i, map_j, normal])
-	}
-
-	fn to_cube_indices(self) -> (usize, usize) {
-		let axis = self.0[2].iter().position(|&e| e != 0).unwrap();
-		let negative = (self.0[2][axis] < 0) as usize;
-		(axis, negative)
-	}
-}
-
-type FaceWithFrame = ([usize; 2], Frame);
-
-fn fold_cube(map: &ArrayView2<'_, Tile>) -> Result<[[FaceWithFrame; 2]; 3]> {
-	if !map.shape().iter().all(|d| d.is_multiple_of(&FACE_SIZE)) {
-		return Err(eyre!(
-			"map does not consist of {FACE_SIZE}×{FACE_SIZE} tiles!"
-		));
-	}
-
-	let ni = map.shape()[0] / FACE_SIZE;
-	let nj = map.shape()[1] / FACE_SIZE;
-
-	fn is_face([i, j]: [usize; 2], map: &ArrayView2<'_, Tile>) -> Result<bool> {
-		let i_first = FACE_SIZE * i;
-		let i_last = FACE_SIZE * (i + 1);
-		let j_first = FACE_SIZE * j;
-		let j_last = FACE_SIZE * (j + 1);
-
-		let face = map.slice(s!(i_first..i_last, j_first..j_last));
-		if face.iter().all(|&t| t == Tile::Warp) {
-			return Ok(false);
-		} else if face.iter().any(|&t| t == Tile::Warp) {
-			return Err(eyre!("unexpected warp tile on face"));
-		}
-
-		Ok(true)
-	}
-
-	let j0 = (0..nj)
-		.position(|j| matches!(is_face([0, j], map), Ok(true)))
-		.ok_or_else(|| eyre!("no valid face in first row"))?;
-
-	let mut cube_map = [[None; 2]; 3];
-	let mut visited = Array2::from_elem((ni, nj), false);
-
-	fn fold_recursion<'a>(
-		ij: [usize; 2],
-		cube_map: &mut [[Option<FaceWithFrame>; 2]; 3],
-		map: &'a ArrayView2<'_, Tile>,
-		visited: &mut Array2<bool>,
-		frame: Frame,
-	) -> Result<()> {
-		if visited[ij] {
-			return Ok(());
-		}
-		visited[ij] = true;
-
-		if !is_face(ij, map)? {
-			return Ok(());
-		}
-
-		let (axis, negative) = frame.to_cube_indices();
-		if cube_map[axis][negative].is_some() {
-			return Err(eyre!("invalid folding pattern"));
-		}
-
-		cube_map[axis][negative] = Some((ij, frame));
-
-		let [i, j] = ij;
-		let (ni, nj) = visited.dim();
-		if i > 0 {
-			fold_recursion([i - 1, j], cube_map, map, visited, frame.prev_i())?;
-		}
-		if i + 1 < ni {
-			fold_recursion([i + 1, j], cube_map, map, visited, frame.next_i())?;
-		}
-		if j > 0 {
-			fold_recursion([i, j - 1], cube_map, map, visited, frame.prev_j())?;
-		}
-		if j + 1 < nj {
-			fold_recursion([i, j + 1], cube_map, map, visited, frame.next_j())?;
-		}
-
-		Ok(())
-	}
-	fold_recursion([0, j0], &mut cube_map, map, &mut visited, Frame::new())?;
-
-	let result = array_init::try_array_init(|axis| {
-		array_init::try_array_init(|negative| {
-			cube_map[axis][negative]
-				.ok_or_else(|| eyre!("folded map does not cover all cube faces"))
-		})
-	})?;
-	Ok(result)
-}
-
-fn read_input() -> Result<(Array2<Tile>, Vec<Instruction>)> {
-	let mut map = Array2::from_elem((0, 0), Tile::Warp);
-	let mut active_rows = 0;
-	let mut in_map = true;
-	let mut instructions = None;
-	try_for_each_line_in_file("inputs/day-22", |line| {
-		let line = line.trim_end();
-
-		// separator
-		if line.is_empty() {
-			in_map = false;
-			return Ok(());
-		}
-
-		// instructions
-		if !in_map {
-			if instructions.is_some() {
-				return Err(eyre!("only one set of instructions expected"));
-			}
-
-			let instructions = instructions.get_or_insert(vec![]);
-			for part in line.split_inclusive(&['R', 'L']) {
-				let (movement, turn) = if let Some(part) = part.strip_suffix('R') {
-					(part, Some(Instruction::Clockwise))
-				} else if let Some(part) = part.strip_suffix('L') {
-					(part, Some(Instruction::Counterclockwise))
-				} else {
-					(part, None)
-				};
-				instructions.push(Instruction::Forward(movement.parse()?));
-				if let Some(turn) = turn {
-					instructions.push(turn);
-				}
-			}
-
-			return Ok(());
-		}
-
-		// map
-		let line = line.as_bytes();
-
-		if active_rows >= map.raw_dim()[0] {
-			let mut dim = map.raw_dim();
-			dim[0] = 1.max(dim[0]);
-			map = concatenate(
-				Axis(0),
-				&[map.view(), Array2::from_elem(dim, Tile::Warp).view()],
-			)?;
-		}
-
-		if line.len() > map.raw_dim()[1] {
-			let mut dim = map.raw_dim();
-			dim[1] = line.len() - dim[1];
-			map = concatenate(
-				Axis(1),
-				&[map.view(), Array2::from_elem(dim, Tile::Warp).view()],
-			)?;
-		}
-
-		for column in 0..line.len() {
-			let tile = match line[column] {
-				b'.' => Tile::Empty,
-				b'#' => Tile::Wall,
-				b' ' => Tile::Warp,
-				_ => return Err(eyre!("invalid map tile {}", line[column])),
-			};
-			map[(active_rows, column)] = tile;
-		}
-
-		active_rows += 1;
-
-		Ok(())
-	})?;
-
-	let instructions = instructions.ok_or_else(|| eyre!("no instructions in input"))?;
-	let map = map.slice(s!(0..active_rows, ..));
-
-	Ok((map.to_owned(), instructions))
-}
-
-fn solution() -> Result<()> {
-	let (map, instructions) = read_input()?;
-	let map = map.view();
-
-	let mut turtle = Turtle::new(map)?;
-	for instruction in &instructions {
-		turtle.process_instruction(*instruction, map);
-	}
-	println!("{}", turtle.value());
-
-	let cube_map = fold_cube(&map)?;
-	let mut turtle = Turtle::new(map)?;
-	for instruction in instructions {
-		turtle.process_instruction_on_cube(instruction, map, cube_map);
-	}
-	println!("{}", turtle.value());
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_22: (usize, fn() -> Result<()>) = (22, solution);
+use crate::generate::InputRng;
+use crate::geometry::{Dir4, Point};
+use crate::grid::{FromGridByte, Grid};
+use crate::lint::Check;
+use crate::sections::Sections;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::prelude::*;
+use num::Integer;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug)]
+enum Instruction {
+	Forward(usize),
+	Clockwise,
+	Counterclockwise,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum Tile {
+	Empty = b'.',
+	Wall = b'#',
+	Warp = b' ',
+}
+
+impl FromGridByte for Tile {
+	fn from_grid_byte(byte: u8) -> Option<Self> {
+		match byte {
+			b'.' => Some(Tile::Empty),
+			b'#' => Some(Tile::Wall),
+			b' ' => Some(Tile::Warp),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Turtle {
+	position: [usize; 2],
+	facing: Dir4,
+}
+
+impl Turtle {
+	fn new(map: ArrayView2<'_, Tile>) -> Result<Turtle> {
+		let start = map
+			.rows()
+			.into_iter()
+			.next()
+			.ok_or_else(|| eyre!("empty map!"))?
+			.into_iter()
+			.position(|t| matches!(t, Tile::Empty))
+			.ok_or_else(|| eyre!("no empty tiles in topmost row!"))?;
+
+		let turtle = Turtle {
+			position: [0, start],
+			facing: Dir4::Right,
+		};
+
+		Ok(turtle)
+	}
+
+	fn process_instruction(&mut self, instruction: Instruction, map: ArrayView2<'_, Tile>) {
+		match instruction {
+			Instruction::Forward(n) => {
+				let (rows, columns) = map.dim();
+				let shape = Point::from_index([rows, columns]);
+				for _ in 0..n {
+					let mut next_tile = Point::from_index(self.position);
+					let next_tile = loop {
+						next_tile = (next_tile + self.facing.offset()).rem_euclid(shape);
+						let next_tile = next_tile
+							.to_index([rows, columns])
+							.expect("wrapped around the map");
+						if map[next_tile] != Tile::Warp {
+							break next_tile;
+						}
+					};
+					if map[next_tile] != Tile::Empty {
+						break;
+					}
+					self.position = next_tile;
+				}
+			}
+			Instruction::Clockwise => {
+				self.facing = self.facing.turn_right();
+			}
+			Instruction::Counterclockwise => {
+				self.facing = self.facing.turn_left();
+			}
+		}
+	}
+
+	fn process_instruction_on_cube(
+		&mut self,
+		instruction: Instruction,
+		map: ArrayView2<'_, Tile>,
+		cube_map: [[FaceWithFrame; 2]; 3],
+		face_size: usize,
+	) {
+		match instruction {
+			Instruction::Forward(n) => {
+				for _ in 0..n {
+					let (rows, columns) = map.dim();
+					let mut next_tile = Point::from_index(self.position) + self.facing.offset();
+					let mut next_facing = self.facing.offset();
+
+					if next_tile
+						.to_index([rows, columns])
+						.is_none_or(|next_tile| map[next_tile] == Tile::Warp)
+					{
+						let current_face = self.position.map(|i| i / face_size);
+						let (_, current_frame) = cube_map
+							.iter()
+							.copied()
+							.flatten()
+							.find(|f| f.0 == current_face)
+							.unwrap();
+						let mut expected_next_frame = match self.facing {
+							Dir4::Right => current_frame.next_j(),
+							Dir4::Left => current_frame.prev_j(),
+							Dir4::Down => current_frame.next_i(),
+							Dir4::Up => current_frame.prev_i(),
+						};
+						let (next_axis, next_negated) = expected_next_frame.to_cube_indices();
+						let (next_face, next_frame) = cube_map[next_axis][next_negated];
+						next_tile = next_tile.rem_euclid(Point([face_size as i64; 2]));
+						if expected_next_frame.0[0].iter().position(|e| *e != 0)
+							!= next_frame.0[0].iter().position(|e| *e != 0)
+						{
+							expected_next_frame.0 = [
+								expected_next_frame.0[1],
+								expected_next_frame.0[0],
+								expected_next_frame.0[2],
+							];
+							next_tile = Point([next_tile[1], next_tile[0]]);
+							next_facing = Point([next_facing[1], next_facing[0]]);
+						}
+						for i in 0..2 {
+							if expected_next_frame.0[i] == next_frame.0[i] {
+								continue;
+							}
+							expected_next_frame.0[i] = expected_next_frame.0[i].map(|e| -e);
+							next_tile[i] = face_size as i64 - next_tile[i] - 1;
+							next_facing[i] = -next_facing[i];
+						}
+						assert_eq!(expected_next_frame, next_frame);
+						next_tile += Point::from_index(next_face) * face_size as i64;
+					}
+
+					let next_tile = next_tile
+						.to_index([rows, columns])
+						.expect("the cube folds onto the map");
+					assert_ne!(map[next_tile], Tile::Warp);
+					if map[next_tile] != Tile::Empty {
+						break;
+					}
+					self.position = next_tile;
+					self.facing = Dir4::from_offset(next_facing).expect("still along an axis");
+				}
+			}
+			_ => self.process_instruction(instruction, map),
+		}
+	}
+
+	fn value(&self) -> usize {
+		1000 * (self.position[0] + 1)
+			+ 4 * (self.position[1] + 1)
+			+ match self.facing {
+				Dir4::Right => 0,
+				Dir4::Down => 1,
+				Dir4::Left => 2,
+				Dir4::Up => 3,
+			}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Frame([[i8; 3]; 3]);
+
+impl Frame {
+	fn new() -> Frame {
+		Frame([[1, 0, 0], [0, 1, 0], [0, 0, 1]])
+	}
+
+	fn next_i(self) -> Frame {
+		let map_i = self.0[2].map(|e| -e);
+		let map_j = self.0[1];
+		let normal = self.0[0];
+		Frame([map_i, map_j, normal])
+	}
+
+	fn prev_i(self) -> Frame {
+		let map_i = self.0[2];
+		let map_j = self.0[1];
+		let normal = self.0[0].map(|e| -e);
+		Frame([map_i, map_j, normal])
+	}
+
+	fn next_j(self) -> Frame {
+		let map_i = self.0[0];
+		let map_j = self.0[2].map(|e| -e);
+		let normal = self.0[1];
+		Frame([map_i, map_j, normal])
+	}
+
+	fn prev_j(self) -> Frame {
+		let map_i = self.0[0];
+		let map_j = self.0[2];
+		let normal = self.0[1].map(|e| -e);
+		Frame([map_i, map_j, normal])
+	}
+
+	fn to_cube_indices(self) -> (usize, usize) {
+		let axis = self.0[2].iter().position(|&e| e != 0).unwrap();
+		let negative = (self.0[2][axis] < 0) as usize;
+		(axis, negative)
+	}
+}
+
+type FaceWithFrame = ([usize; 2], Frame);
+
+// each of the six faces covers face_size² tiles of the map
+fn face_size(map: &ArrayView2<'_, Tile>) -> Result<usize> {
+	let area = map.iter().filter(|&&t| t != Tile::Warp).count();
+	let face_size = (area as f64 / 6.0).sqrt().round() as usize;
+	if face_size == 0 || 6 * face_size * face_size != area {
+		return Err(eyre!("map area {area} can't be folded into a cube"));
+	}
+	Ok(face_size)
+}
+
+fn fold_cube(map: &ArrayView2<'_, Tile>, face_size: usize) -> Result<[[FaceWithFrame; 2]; 3]> {
+	if !map.shape().iter().all(|d| d.is_multiple_of(&face_size)) {
+		return Err(eyre!(
+			"map does not consist of {face_size}×{face_size} tiles!"
+		));
+	}
+
+	let ni = map.shape()[0] / face_size;
+	let nj = map.shape()[1] / face_size;
+
+	fn is_face([i, j]: [usize; 2], map: &ArrayView2<'_, Tile>, face_size: usize) -> Result<bool> {
+		let i_first = face_size * i;
+		let i_last = face_size * (i + 1);
+		let j_first = face_size * j;
+		let j_last = face_size * (j + 1);
+
+		let face = map.slice(s!(i_first..i_last, j_first..j_last));
+		if face.iter().all(|&t| t == Tile::Warp) {
+			return Ok(false);
+		} else if face.iter().any(|&t| t == Tile::Warp) {
+			return Err(eyre!("unexpected warp tile on face"));
+		}
+
+		Ok(true)
+	}
+
+	let j0 = (0..nj)
+		.position(|j| matches!(is_face([0, j], map, face_size), Ok(true)))
+		.ok_or_else(|| eyre!("no valid face in first row"))?;
+
+	let mut cube_map = [[None; 2]; 3];
+	let mut visited = Array2::from_elem((ni, nj), false);
+
+	fn fold_recursion(
+		ij: [usize; 2],
+		cube_map: &mut [[Option<FaceWithFrame>; 2]; 3],
+		map: &ArrayView2<'_, Tile>,
+		visited: &mut Array2<bool>,
+		frame: Frame,
+		face_size: usize,
+	) -> Result<()> {
+		if visited[ij] {
+			return Ok(());
+		}
+		visited[ij] = true;
+
+		if !is_face(ij, map, face_size)? {
+			return Ok(());
+		}
+
+		let (axis, negative) = frame.to_cube_indices();
+		if cube_map[axis][negative].is_some() {
+			return Err(eyre!("invalid folding pattern"));
+		}
+
+		cube_map[axis][negative] = Some((ij, frame));
+
+		let [i, j] = ij;
+		let (ni, nj) = visited.dim();
+		if i > 0 {
+			fold_recursion(
+				[i - 1, j],
+				cube_map,
+				map,
+				visited,
+				frame.prev_i(),
+				face_size,
+			)?;
+		}
+		if i + 1 < ni {
+			fold_recursion(
+				[i + 1, j],
+				cube_map,
+				map,
+				visited,
+				frame.next_i(),
+				face_size,
+			)?;
+		}
+		if j > 0 {
+			fold_recursion(
+				[i, j - 1],
+				cube_map,
+				map,
+				visited,
+				frame.prev_j(),
+				face_size,
+			)?;
+		}
+		if j + 1 < nj {
+			fold_recursion(
+				[i, j + 1],
+				cube_map,
+				map,
+				visited,
+				frame.next_j(),
+				face_size,
+			)?;
+		}
+
+		Ok(())
+	}
+	fold_recursion(
+		[0, j0],
+		&mut cube_map,
+		map,
+		&mut visited,
+		Frame::new(),
+		face_size,
+	)?;
+
+	let result = array_init::try_array_init(|axis| {
+		array_init::try_array_init(|negative| {
+			cube_map[axis][negative]
+				.ok_or_else(|| eyre!("folded map does not cover all cube faces"))
+		})
+	})?;
+	Ok(result)
+}
+
+fn read_instructions(line: &str) -> Result<Vec<Instruction>> {
+	let mut instructions = vec![];
+	for part in line.split_inclusive(&['R', 'L']) {
+		let (movement, turn) = if let Some(part) = part.strip_suffix('R') {
+			(part, Some(Instruction::Clockwise))
+		} else if let Some(part) = part.strip_suffix('L') {
+			(part, Some(Instruction::Counterclockwise))
+		} else {
+			(part, None)
+		};
+		instructions.push(Instruction::Forward(movement.parse()?));
+		if let Some(turn) = turn {
+			instructions.push(turn);
+		}
+	}
+	Ok(instructions)
+}
+
+fn read_input(input: &str) -> Result<(Array2<Tile>, Vec<Instruction>)> {
+	let mut sections = Sections::new(input);
+	// the map doesn't draw the void to the right of its faces
+	let map = sections.block("map", |map| Grid::parse_padded(map, Tile::Warp))?;
+	let instructions = sections.block("path", |path| {
+		if path.lines().count() > 1 {
+			return Err(eyre!("only one set of instructions expected"));
+		}
+		read_instructions(path.trim())
+	})?;
+	sections.finish()?;
+
+	Ok((map.into(), instructions))
+}
+
+struct Day22;
+
+impl Puzzle for Day22 {
+	type Parsed = (Array2<Tile>, Vec<Instruction>);
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_input(input)
+	}
+
+	fn part_1((map, instructions): &Self::Parsed) -> Result<Answer> {
+		let map = map.view();
+		let mut turtle = Turtle::new(map)?;
+		for instruction in instructions {
+			turtle.process_instruction(*instruction, map);
+		}
+		Ok(turtle.value().into())
+	}
+
+	fn part_2((map, instructions): &Self::Parsed) -> Result<Answer> {
+		let map = map.view();
+		let face_size = face_size(&map)?;
+		let cube_map = fold_cube(&map, face_size)?;
+		let mut turtle = Turtle::new(map)?;
+		for instruction in instructions {
+			turtle.process_instruction_on_cube(*instruction, map, cube_map, face_size);
+		}
+		Ok(turtle.value().into())
+	}
+
+	fn lint(input: &str) -> Result<Vec<Check>> {
+		let (map, _) = read_input(input)?;
+		let map = map.view();
+		// any size of faces works, not just the 50×50 of the actual inputs
+		let cube = face_size(&map).and_then(|face_size| fold_cube(&map, face_size).map(drop));
+		Ok(vec![Check::new("the map folds into a cube", cube)])
+	}
+
+	/// Generates a random net of a cube with faces of `size` by `size` tiles, and a path of
+	/// `4 * size` moves.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let size = size.max(1);
+		// grows random hexominoes until one folds into a cube, checked with faces of a single tile
+		let net = loop {
+			let mut faces = vec![[0i64, 0]];
+			while faces.len() < 6 {
+				let [i, j] = *faces.choose(rng).expect("not empty");
+				let face = *[[i - 1, j], [i + 1, j], [i, j - 1], [i, j + 1]]
+					.choose(rng)
+					.expect("not empty");
+				if !faces.contains(&face) {
+					faces.push(face);
+				}
+			}
+			let i_min = faces.iter().map(|[i, _]| *i).min().unwrap_or(0);
+			let j_min = faces.iter().map(|[_, j]| *j).min().unwrap_or(0);
+			let faces: Vec<[usize; 2]> = faces
+				.iter()
+				.map(|&[i, j]| [(i - i_min) as usize, (j - j_min) as usize])
+				.collect();
+			let rows = faces.iter().map(|[i, _]| i + 1).max().unwrap_or(0);
+			let columns = faces.iter().map(|[_, j]| j + 1).max().unwrap_or(0);
+			let net = Array2::from_shape_fn((rows, columns), |(i, j)| {
+				if faces.contains(&[i, j]) {
+					Tile::Empty
+				} else {
+					Tile::Warp
+				}
+			});
+			if fold_cube(&net.view(), 1).is_ok() {
+				break net;
+			}
+		};
+
+		let mut input = String::new();
+		for row in 0..net.nrows() * size {
+			let mut line: String = (0..net.ncols() * size)
+				.map(|column| match net[[row / size, column / size]] {
+					Tile::Warp => ' ',
+					_ if rng.gen_ratio(1, 10) => '#',
+					_ => '.',
+				})
+				.collect();
+			// the path starts on an open tile of the top row
+			if row == 0 && !line.contains('.') {
+				line = line.replacen('#', ".", 1);
+			}
+			input += line.trim_end();
+			input.push('\n');
+		}
+		input.push('\n');
+
+		input += &rng.gen_range(1..=2 * size).to_string();
+		for _ in 1..4 * size {
+			let turn = if rng.gen_bool(0.5) { 'R' } else { 'L' };
+			input += &format!("{turn}{}", rng.gen_range(1..=2 * size));
+		}
+		input.push('\n');
+		Ok(input)
+	}
+}
+
+solution!(Day22, day = 22, title = "Monkey Map");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = concat!(
+		"        ...#\n",
+		"        .#..\n",
+		"        #...\n",
+		"        ....\n",
+		"...#.......#\n",
+		"........#...\n",
+		"..#....#....\n",
+		"..........#.\n",
+		"        ...#....\n",
+		"        .....#..\n",
+		"        .#......\n",
+		"        ......#.\n",
+		"\n",
+		"10R5L5R10L4R5L5\n",
+	);
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day22::solve(EXAMPLE)?, Answers::new(6032, 5031));
+		Ok(())
+	}
+}
@@ -1,119 +1,141 @@
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
-
-fn read_input() -> Result<HashSet<[isize; 2]>> {
-	let mut result = HashSet::default();
-	let mut row = 0;
-	try_for_each_line_in_file("inputs/day-23", |line| {
-		let line = line.trim();
-		for (column, value) in line.as_bytes().iter().copied().enumerate() {
-			match value {
-				b'#' => {
-					result.insert([row, column as isize]);
-				}
-				b'.' => {}
-				_ => return Err(eyre!("invalid input {value}")),
-			};
-		}
-		row += 1;
-		Ok(())
-	})?;
-	Ok(result)
-}
-
-fn plan_move(
-	elves: &HashSet<[isize; 2]>,
-	round: usize,
-	plan: &mut HashMap<[isize; 2], Option<[isize; 2]>>,
-) {
-	plan.clear();
-	let mut directions = [
-		0usize, // N
-		4,      // S
-		2,      // W
-		6,      // E
-	];
-	directions.rotate_left(round % 4);
-
-	'outer: for &elf in elves {
-		let neighbor_positions = [
-			[elf[0] - 1, elf[1]],     // N
-			[elf[0] - 1, elf[1] - 1], // NW
-			[elf[0], elf[1] - 1],     // W
-			[elf[0] + 1, elf[1] - 1], // SW
-			[elf[0] + 1, elf[1]],     // S
-			[elf[0] + 1, elf[1] + 1], // SE
-			[elf[0], elf[1] + 1],     // E
-			[elf[0] - 1, elf[1] + 1], // NE
-		];
-
-		let neighbors = neighbor_positions.map(|elf| elves.contains(&elf));
-		if neighbors.iter().all(|&n| !n) {
-			continue;
-		}
-
-		for direction in directions {
-			let offset = direction.checked_sub(1).unwrap_or(7);
-			if neighbors.iter().cycle().skip(offset).take(3).all(|&n| !n) {
-				plan.entry(neighbor_positions[direction])
-					.and_modify(|e| *e = None)
-					.or_insert(Some(elf));
-				continue 'outer;
-			}
-		}
-	}
-}
-
-fn run_plan(elves: &mut HashSet<[isize; 2]>, plan: &HashMap<[isize; 2], Option<[isize; 2]>>) {
-	for (&k, v) in plan {
-		if let Some(v) = v {
-			elves.remove(v);
-			elves.insert(k);
-		}
-	}
-}
-
-fn bounds(elves: &HashSet<[isize; 2]>) -> [[isize; 2]; 2] {
-	let mut lo = [isize::MAX; 2];
-	let mut hi = [isize::MIN; 2];
-	for elf in elves {
-		lo.iter_mut().zip(elf).for_each(|(b, v)| *b = (*b).min(*v));
-		hi.iter_mut().zip(elf).for_each(|(b, v)| *b = (*b).max(*v));
-	}
-	[lo, hi]
-}
-
-fn empty_ground(elves: &HashSet<[isize; 2]>) -> usize {
-	let [lo, hi] = bounds(elves);
-	lo.into_iter()
-		.zip(hi)
-		.map(|(lo, hi)| (hi - lo + 1) as usize)
-		.product::<usize>()
-		- elves.len()
-}
-
-fn solution() -> Result<()> {
-	let mut elves = read_input()?;
-	let mut plan = HashMap::default();
-
-	for round in 0.. {
-		plan_move(&elves, round, &mut plan);
-		if plan.values().all(Option::is_none) {
-			println!("{}", round + 1);
-			break;
-		}
-		run_plan(&mut elves, &plan);
-		if round == 9 {
-			println!("{}", empty_ground(&elves));
-		}
-	}
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_23: (usize, fn() -> Result<()>) = (23, solution);
+use crate::generate::InputRng;
+use crate::geometry::{Bounds, Dir8, Point};
+use crate::grid::Grid;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::Result;
+use rand::Rng;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+fn read_input(input: &str) -> Result<HashSet<Point<2>>> {
+	let map = Grid::parse_with(input, |byte| match byte {
+		b'#' => Some(true),
+		b'.' => Some(false),
+		_ => None,
+	})?;
+	Ok(map
+		.indexed_iter()
+		.filter(|(_, &elf)| elf)
+		.map(|((row, column), _)| Point::from_index([row, column]))
+		.collect())
+}
+
+// the directions the elves consider moving in during the first round
+const PROPOSALS: [Dir8; 4] = [Dir8::N, Dir8::S, Dir8::W, Dir8::E];
+
+fn plan_move(
+	elves: &HashSet<Point<2>>,
+	round: usize,
+	plan: &mut HashMap<Point<2>, Option<Point<2>>>,
+) {
+	plan.clear();
+	let mut directions = PROPOSALS;
+	directions.rotate_left(round % 4);
+
+	'outer: for &elf in elves {
+		let occupied = Dir8::ALL.map(|direction| elves.contains(&(elf + direction.offset())));
+		if occupied.iter().all(|&n| !n) {
+			continue;
+		}
+
+		for direction in directions {
+			if [direction.turn_left(), direction, direction.turn_right()]
+				.iter()
+				.all(|&d| !occupied[d as usize])
+			{
+				plan.entry(elf + direction.offset())
+					.and_modify(|e| *e = None)
+					.or_insert(Some(elf));
+				continue 'outer;
+			}
+		}
+	}
+}
+
+fn run_plan(elves: &mut HashSet<Point<2>>, plan: &HashMap<Point<2>, Option<Point<2>>>) {
+	for (&k, v) in plan {
+		if let Some(v) = v {
+			elves.remove(v);
+			elves.insert(k);
+		}
+	}
+}
+
+fn empty_ground(elves: &HashSet<Point<2>>) -> usize {
+	Bounds::from_points(elves.iter().copied()).map_or(0, |bounds| bounds.volume()) - elves.len()
+}
+
+struct Day23;
+
+impl Puzzle for Day23 {
+	type Parsed = HashSet<Point<2>>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		read_input(input)
+	}
+
+	fn part_1(elves: &Self::Parsed) -> Result<Answer> {
+		let mut elves = elves.clone();
+		let mut plan = HashMap::default();
+
+		for round in 0..10 {
+			plan_move(&elves, round, &mut plan);
+			run_plan(&mut elves, &plan);
+		}
+
+		Ok(empty_ground(&elves).into())
+	}
+
+	fn part_2(elves: &Self::Parsed) -> Result<Answer> {
+		let mut elves = elves.clone();
+		let mut plan = HashMap::default();
+
+		for round in 0.. {
+			plan_move(&elves, round, &mut plan);
+			if plan.values().all(Option::is_none) {
+				return Ok((round + 1).into());
+			}
+			run_plan(&mut elves, &plan);
+		}
+
+		unreachable!()
+	}
+
+	/// Generates a grove of `size` by `size` tiles, about half of them with an elf.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let size = size.max(1);
+		Ok((0..size)
+			.map(|_| {
+				let mut row: String = (0..size)
+					.map(|_| if rng.gen_bool(0.5) { '#' } else { '.' })
+					.collect();
+				row.push('\n');
+				row
+			})
+			.collect())
+	}
+}
+
+solution!(Day23, day = 23, title = "Unstable Diffusion");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day23::solve(EXAMPLE)?, Answers::new(110, 20));
+		Ok(())
+	}
+}
@@ -1,325 +1,479 @@
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-use ndarray::{concatenate, prelude::*};
-use pathfinding::directed::astar::astar;
-
-const WIND_SYMBOLS: [u8; 4] = [b'^', b'<', b'v', b'>'];
-const WIND_DIRECTIONS: [[isize; 2]; 4] = [[-1isize, 0], [0, -1], [1, 0], [0, 1]];
-
-fn read_input() -> Result<Array3<bool>> {
-	let mut blizzards = Array3::from_elem((4, 0, 0), false);
-	let mut valid_lines = 0;
-	let mut done = false;
-	try_for_each_line_in_file("inputs/day-24", |line| {
-		let line = line.trim();
-
-		if done {
-			return Err(eyre!("unexpected line after input: {line}"));
-		}
-
-		if blizzards.dim().1 == 0 {
-			let line = line
-				.strip_prefix("#.")
-				.ok_or_else(|| eyre!("start not at expected position"))?;
-			if !line.as_bytes().iter().all(|&b| b == b'#') {
-				return Err(eyre!("missing northern wall"));
-			}
-			blizzards = Array3::from_elem((4, 1, line.len()), false);
-			return Ok(());
-		} else if line
-			.strip_suffix(".#")
-			.map(|line| line.as_bytes().iter().all(|&b| b == b'#'))
-			== Some(true)
-		{
-			// in the future, the above condition could be simplified using is_some_and
-			done = true;
-			return Ok(());
-		}
-
-		let line = line
-			.strip_prefix('#')
-			.and_then(|line| line.strip_suffix('#'))
-			.ok_or_else(|| eyre!("missing side walls"))?
-			.as_bytes();
-
-		if line.len() != blizzards.dim().2 {
-			return Err(eyre!("mismatched line width"));
-		}
-
-		if line.iter().any(|b| *b != b'.' && !WIND_SYMBOLS.contains(b)) {
-			return Err(eyre!("invalid input"));
-		}
-
-		if valid_lines >= blizzards.dim().1 {
-			let dim = blizzards.raw_dim();
-			blizzards = concatenate(
-				Axis(1),
-				&[blizzards.view(), Array3::from_elem(dim, false).view()],
-			)?;
-		}
-
-		blizzards
-			.axis_iter_mut(Axis(0))
-			.zip(WIND_SYMBOLS)
-			.for_each(|(mut blizzard, marker)| {
-				blizzard
-					.slice_mut(s!(valid_lines, ..))
-					.iter_mut()
-					.zip(line)
-					.for_each(|(e, &v)| {
-						*e = v == marker;
-					})
-			});
-
-		valid_lines += 1;
-
-		Ok(())
-	})?;
-
-	if !done {
-		return Err(eyre!("missing southern wall"));
-	}
-
-	let blizzards = blizzards.slice(s!(.., 0..valid_lines, ..)).to_owned();
-
-	if blizzards.slice(s!(0, .., 0)).iter().any(|&b| b)
-		|| blizzards.slice(s!(0, .., -1)).iter().any(|&b| b)
-		|| blizzards.slice(s!(2, .., 0)).iter().any(|&b| b)
-		|| blizzards.slice(s!(2, .., -1)).iter().any(|&b| b)
-	{
-		return Err(eyre!("unexpected blizzards at sides"));
-	}
-
-	Ok(blizzards)
-}
-
-fn blizzards_at_point_and_time(
-	[row, col]: [usize; 2],
-	blizzards: &Array3<bool>,
-	time: usize,
-) -> [bool; 4] {
-	let (directions, rows, cols) = blizzards.dim();
-	assert_eq!(directions, 4);
-	array_init::array_init(|i| {
-		let [v_row, v_col] = WIND_DIRECTIONS[i];
-		let row = (row as isize - time as isize * v_row).rem_euclid(rows as isize) as usize;
-		let col = (col as isize - time as isize * v_col).rem_euclid(cols as isize) as usize;
-		blizzards[(i, row, col)]
-	})
-}
-
-fn any_blizzards_at_point_and_time(
-	position: [usize; 2],
-	blizzards: &Array3<bool>,
-	time: usize,
-) -> bool {
-	blizzards_at_point_and_time(position, blizzards, time)
-		.into_iter()
-		.any(|b| b)
-}
-
-fn _print_at_time(blizzards: &Array3<bool>, time: usize) {
-	let (_, rows, cols) = blizzards.dim();
-	for row in 0..rows {
-		for col in 0..cols {
-			let blizzards = blizzards_at_point_and_time([row, col], blizzards, time);
-			let n = blizzards.iter().filter(|&&b| b).count();
-			match n {
-				0 => print!("."),
-				1 => blizzards.iter().zip(WIND_SYMBOLS).for_each(|(&b, s)| {
-					if b {
-						print!("{}", s as char)
-					}
-				}),
-				_ => print!("{n}"),
-			}
-		}
-		println!();
-	}
-	println!();
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-enum Position {
-	Start,
-	End,
-	Interior([usize; 2]),
-}
-
-impl Position {
-	fn interior(self) -> Option<[usize; 2]> {
-		match self {
-			Position::Interior(p) => Some(p),
-			_ => None,
-		}
-	}
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-struct State {
-	position: Position,
-	time: usize,
-}
-
-impl State {
-	fn new() -> State {
-		State {
-			position: Position::Start,
-			time: 0,
-		}
-	}
-
-	fn successors_interior<'a>(
-		&self,
-		blizzards: &'a Array3<bool>,
-	) -> impl IntoIterator<Item = State> + 'a {
-		let State { position, time } = *self;
-		let time = time + 1;
-		position.interior().into_iter().flat_map(move |p| {
-			[[-1isize, 0], [0, -1], [1, 0], [0, 1], [0, 0]]
-				.into_iter()
-				.filter_map(move |[vr, vc]| {
-					let [r, c] = p;
-					let r = r.checked_add_signed(vr)?;
-					let c = c.checked_add_signed(vc)?;
-					if r >= blizzards.dim().1
-						|| c >= blizzards.dim().2
-						|| any_blizzards_at_point_and_time([r, c], blizzards, time)
-					{
-						return None;
-					}
-					Some(State {
-						position: Position::Interior([r, c]),
-						time,
-					})
-				})
-		})
-	}
-
-	fn successors<'a>(
-		&self,
-		blizzards: &'a Array3<bool>,
-	) -> impl IntoIterator<Item = (State, usize)> + 'a {
-		let State { position, time } = *self;
-		let time = time + 1;
-		let start = (position == Position::Start)
-			.then(|| {
-				[
-					(!any_blizzards_at_point_and_time([0, 0], blizzards, time)).then_some(State {
-						position: Position::Interior([0, 0]),
-						time,
-					}),
-					Some(State { position, time }),
-				]
-				.into_iter()
-				.flatten()
-			})
-			.into_iter()
-			.flatten();
-		let end = (position == Position::Interior([blizzards.dim().1 - 1, blizzards.dim().2 - 1]))
-			.then_some(State {
-				position: Position::End,
-				time,
-			});
-		start
-			.chain(end)
-			.chain(self.successors_interior(blizzards))
-			.map(|state| (state, 1))
-	}
-
-	fn successors_rev<'a>(
-		&self,
-		blizzards: &'a Array3<bool>,
-	) -> impl IntoIterator<Item = (State, usize)> + 'a {
-		let State { position, time } = *self;
-		let time = time + 1;
-		let start = (position == Position::End)
-			.then(|| {
-				let start = [blizzards.dim().1 - 1, blizzards.dim().2 - 1];
-				[
-					(!any_blizzards_at_point_and_time(start, blizzards, time)).then_some(State {
-						position: Position::Interior(start),
-						time,
-					}),
-					Some(State { position, time }),
-				]
-				.into_iter()
-				.flatten()
-			})
-			.into_iter()
-			.flatten();
-		let end = (position == Position::Interior([0, 0])).then_some(State {
-			position: Position::Start,
-			time,
-		});
-		start
-			.chain(end)
-			.chain(self.successors_interior(blizzards))
-			.map(|state| (state, 1))
-	}
-
-	fn heuristic(&self, blizzards: &Array3<bool>) -> usize {
-		match self.position {
-			Position::Start => blizzards.dim().1 + blizzards.dim().2,
-			Position::End => 0,
-			Position::Interior([r, c]) => blizzards.dim().1 - r + blizzards.dim().2 - c - 1,
-		}
-	}
-
-	fn heuristic_rev(&self, blizzards: &Array3<bool>) -> usize {
-		match self.position {
-			Position::Start => 0,
-			Position::End => blizzards.dim().1 + blizzards.dim().2,
-			Position::Interior([r, c]) => r + c + 1,
-		}
-	}
-
-	fn success(&self) -> bool {
-		self.position == Position::End
-	}
-
-	fn success_rev(&self) -> bool {
-		self.position == Position::Start
-	}
-}
-
-fn solution() -> Result<()> {
-	let blizzards = read_input()?;
-
-	let (path, time_1) = astar(
-		&State::new(),
-		|state| state.successors(&blizzards),
-		|state| state.heuristic(&blizzards),
-		State::success,
-	)
-	.ok_or_else(|| eyre!("no path to exit"))?;
-
-	println!("{time_1}");
-
-	let (path, time_2) = astar(
-		path.last().unwrap(),
-		|state| state.successors_rev(&blizzards),
-		|state| state.heuristic_rev(&blizzards),
-		State::success_rev,
-	)
-	.ok_or_else(|| eyre!("no path to entrance"))?;
-
-	let (_, time_3) = astar(
-		path.last().unwrap(),
-		|state| state.successors(&blizzards),
-		|state| state.heuristic(&blizzards),
-		State::success,
-	)
-	.ok_or_else(|| eyre!("no path to entrance"))?;
-
-	let time_with_return = time_1 + time_2 + time_3;
-	println!("{time_with_return}");
-
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_24: (usize, fn() -> Result<()>) = (24, solution);
+use std::collections::VecDeque;
+
+use crate::cancel::CancellationToken;
+use crate::generate::InputRng;
+use crate::geometry::{grid_neighbors, Dir4, Point};
+use crate::grid::{FromGridByte, Grid};
+use crate::lint::{self, Check};
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::prelude::*;
+use num::Integer;
+use pathfinding::directed::astar::astar;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+const WIND_SYMBOLS: [u8; 4] = [b'^', b'<', b'v', b'>'];
+const WIND_DIRECTIONS: [Dir4; 4] = [Dir4::Up, Dir4::Left, Dir4::Down, Dir4::Right];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tile {
+	Wall,
+	Clear,
+	/// The index of its direction in [`WIND_SYMBOLS`].
+	Blizzard(usize),
+}
+
+impl FromGridByte for Tile {
+	fn from_grid_byte(byte: u8) -> Option<Self> {
+		match byte {
+			b'#' => Some(Tile::Wall),
+			b'.' => Some(Tile::Clear),
+			_ => WIND_SYMBOLS
+				.iter()
+				.position(|&symbol| symbol == byte)
+				.map(Tile::Blizzard),
+		}
+	}
+}
+
+fn read_input(input: &str) -> Result<Array3<bool>> {
+	let map = Grid::<Tile>::parse(input)?;
+	let (rows, columns) = map.dim();
+	if rows < 3 || columns < 3 {
+		return Err(eyre!("map too small"));
+	}
+
+	// the entrance and the exit are the only gaps in the northern and southern walls
+	let is_wall_with_gap = |wall: ArrayView1<'_, Tile>, gap: usize| {
+		wall.iter().enumerate().all(|(column, &tile)| {
+			tile == if column == gap {
+				Tile::Clear
+			} else {
+				Tile::Wall
+			}
+		})
+	};
+	if !is_wall_with_gap(map.row(0), 1) {
+		return Err(eyre!("start not at expected position"));
+	}
+	if !is_wall_with_gap(map.row(rows - 1), columns - 2) {
+		return Err(eyre!("missing southern wall"));
+	}
+	if map
+		.slice(s!(1..rows - 1, 0))
+		.iter()
+		.chain(map.slice(s!(1..rows - 1, -1)))
+		.any(|&tile| tile != Tile::Wall)
+	{
+		return Err(eyre!("missing side walls"));
+	}
+
+	let interior = map.slice(s!(1..rows - 1, 1..columns - 1));
+	if interior.iter().any(|&tile| tile == Tile::Wall) {
+		return Err(eyre!("invalid input"));
+	}
+	let blizzards =
+		Array3::from_shape_fn((4, rows - 2, columns - 2), |(direction, row, column)| {
+			interior[(row, column)] == Tile::Blizzard(direction)
+		});
+	Ok(blizzards)
+}
+
+// a blizzard going up or down in the column of the entrance or the exit would blow through it
+// instead of wrapping around
+fn check_blizzards(blizzards: &Array3<bool>) -> Vec<Check> {
+	let vertical = if blizzards.slice(s!(0, .., 0)).iter().any(|&b| b)
+		|| blizzards.slice(s!(0, .., -1)).iter().any(|&b| b)
+		|| blizzards.slice(s!(2, .., 0)).iter().any(|&b| b)
+		|| blizzards.slice(s!(2, .., -1)).iter().any(|&b| b)
+	{
+		Err(eyre!("unexpected blizzards at sides"))
+	} else {
+		Ok(())
+	};
+	vec![Check::new(
+		"no blizzard goes up or down the columns of the entrance and the exit",
+		vertical,
+	)]
+}
+
+// the search for the fastest way only gives up once it has seen every state, and there are
+// endlessly many of those when waiting at the entrance is always safe
+fn check_crossing(blizzards: &Array3<bool>) -> Check {
+	let (_, rows, columns) = blizzards.dim();
+	let (entrance, exit) = ([0, 0], [rows - 1, columns - 1]);
+	let crossing = crossing_time(blizzards, entrance, exit, 0)
+		.and_then(|time| crossing_time(blizzards, exit, entrance, time + 1))
+		.and_then(|time| crossing_time(blizzards, entrance, exit, time + 1))
+		.map(|_| ())
+		.ok_or_else(|| eyre!("the blizzards block the way for good"));
+	Check::new(
+		"there is a way to the exit, back and to the exit again",
+		crossing,
+	)
+}
+
+/// When the expedition can first be on `to` if it's outside next to `from` at `time`. Follows
+/// every way at once: waiting outside for a cycle of the blizzards can only reach more tiles than
+/// before, so once it doesn't, `to` is out of reach.
+fn crossing_time(
+	blizzards: &Array3<bool>,
+	from: [usize; 2],
+	to: [usize; 2],
+	mut time: usize,
+) -> Option<usize> {
+	let (_, rows, columns) = blizzards.dim();
+	let cycle = rows.lcm(&columns);
+	let mut reached = Array2::from_elem((rows, columns), false);
+	let mut counts = VecDeque::from([0]);
+	loop {
+		time += 1;
+		reached = Array2::from_shape_fn((rows, columns), |(row, column)| {
+			let position = [row, column];
+			(position == from
+				|| reached[position]
+				|| grid_neighbors(position, [rows, columns]).any(|p| reached[p]))
+				&& !any_blizzards_at_point_and_time(position, blizzards, time)
+		});
+		if reached[to] {
+			return Some(time);
+		}
+		let count = reached.iter().filter(|&&r| r).count();
+		if counts.len() == cycle && counts.pop_front() == Some(count) {
+			return None;
+		}
+		counts.push_back(count);
+	}
+}
+
+fn blizzards_at_point_and_time(
+	[row, col]: [usize; 2],
+	blizzards: &Array3<bool>,
+	time: usize,
+) -> [bool; 4] {
+	let (directions, rows, cols) = blizzards.dim();
+	assert_eq!(directions, 4);
+	array_init::array_init(|i| {
+		// where the blizzard came from
+		let [row, col] =
+			(Point::from_index([row, col]) - WIND_DIRECTIONS[i].offset() * time as i64).0;
+		let row = row.rem_euclid(rows as i64) as usize;
+		let col = col.rem_euclid(cols as i64) as usize;
+		blizzards[(i, row, col)]
+	})
+}
+
+fn any_blizzards_at_point_and_time(
+	position: [usize; 2],
+	blizzards: &Array3<bool>,
+	time: usize,
+) -> bool {
+	blizzards_at_point_and_time(position, blizzards, time)
+		.into_iter()
+		.any(|b| b)
+}
+
+fn _print_at_time(blizzards: &Array3<bool>, time: usize) {
+	let (_, rows, cols) = blizzards.dim();
+	for row in 0..rows {
+		for col in 0..cols {
+			let blizzards = blizzards_at_point_and_time([row, col], blizzards, time);
+			let n = blizzards.iter().filter(|&&b| b).count();
+			match n {
+				0 => print!("."),
+				1 => blizzards.iter().zip(WIND_SYMBOLS).for_each(|(&b, s)| {
+					if b {
+						print!("{}", s as char)
+					}
+				}),
+				_ => print!("{n}"),
+			}
+		}
+		println!();
+	}
+	println!();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Position {
+	Start,
+	End,
+	Interior([usize; 2]),
+}
+
+impl Position {
+	fn interior(self) -> Option<[usize; 2]> {
+		match self {
+			Position::Interior(p) => Some(p),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct State {
+	position: Position,
+	time: usize,
+}
+
+impl State {
+	fn new() -> State {
+		State {
+			position: Position::Start,
+			time: 0,
+		}
+	}
+
+	fn successors_interior<'a>(
+		&self,
+		blizzards: &'a Array3<bool>,
+	) -> impl IntoIterator<Item = State> + 'a {
+		let State { position, time } = *self;
+		let time = time + 1;
+		let (_, rows, cols) = blizzards.dim();
+		position.interior().into_iter().flat_map(move |p| {
+			// waiting is a move too
+			grid_neighbors(p, [rows, cols])
+				.chain([p])
+				.filter(move |&p| !any_blizzards_at_point_and_time(p, blizzards, time))
+				.map(move |p| State {
+					position: Position::Interior(p),
+					time,
+				})
+		})
+	}
+
+	fn successors<'a>(
+		&self,
+		blizzards: &'a Array3<bool>,
+	) -> impl IntoIterator<Item = (State, usize)> + 'a {
+		let State { position, time } = *self;
+		let time = time + 1;
+		let start = (position == Position::Start)
+			.then(|| {
+				[
+					(!any_blizzards_at_point_and_time([0, 0], blizzards, time)).then_some(State {
+						position: Position::Interior([0, 0]),
+						time,
+					}),
+					Some(State { position, time }),
+				]
+				.into_iter()
+				.flatten()
+			})
+			.into_iter()
+			.flatten();
+		let end = (position == Position::Interior([blizzards.dim().1 - 1, blizzards.dim().2 - 1]))
+			.then_some(State {
+				position: Position::End,
+				time,
+			});
+		start
+			.chain(end)
+			.chain(self.successors_interior(blizzards))
+			.map(|state| (state, 1))
+	}
+
+	fn successors_rev<'a>(
+		&self,
+		blizzards: &'a Array3<bool>,
+	) -> impl IntoIterator<Item = (State, usize)> + 'a {
+		let State { position, time } = *self;
+		let time = time + 1;
+		let start = (position == Position::End)
+			.then(|| {
+				let start = [blizzards.dim().1 - 1, blizzards.dim().2 - 1];
+				[
+					(!any_blizzards_at_point_and_time(start, blizzards, time)).then_some(State {
+						position: Position::Interior(start),
+						time,
+					}),
+					Some(State { position, time }),
+				]
+				.into_iter()
+				.flatten()
+			})
+			.into_iter()
+			.flatten();
+		let end = (position == Position::Interior([0, 0])).then_some(State {
+			position: Position::Start,
+			time,
+		});
+		start
+			.chain(end)
+			.chain(self.successors_interior(blizzards))
+			.map(|state| (state, 1))
+	}
+
+	fn heuristic(&self, blizzards: &Array3<bool>) -> usize {
+		match self.position {
+			Position::Start => blizzards.dim().1 + blizzards.dim().2,
+			Position::End => 0,
+			Position::Interior([r, c]) => blizzards.dim().1 - r + blizzards.dim().2 - c - 1,
+		}
+	}
+
+	fn heuristic_rev(&self, blizzards: &Array3<bool>) -> usize {
+		match self.position {
+			Position::Start => 0,
+			Position::End => blizzards.dim().1 + blizzards.dim().2,
+			Position::Interior([r, c]) => r + c + 1,
+		}
+	}
+
+	fn success(&self) -> bool {
+		self.position == Position::End
+	}
+
+	fn success_rev(&self) -> bool {
+		self.position == Position::Start
+	}
+}
+
+// once cancelled there are no more successors, so the search runs out of states to visit
+fn there(
+	blizzards: &Array3<bool>,
+	start: &State,
+	cancel: &CancellationToken,
+) -> Result<(Vec<State>, usize)> {
+	let path = astar(
+		start,
+		|state| {
+			let cancelled = cancel.is_cancelled();
+			state
+				.successors(blizzards)
+				.into_iter()
+				.filter(move |_| !cancelled)
+		},
+		|state| state.heuristic(blizzards),
+		State::success,
+	);
+	cancel.check()?;
+	path.ok_or_else(|| eyre!("no path to exit"))
+}
+
+fn back_again(
+	blizzards: &Array3<bool>,
+	start: &State,
+	cancel: &CancellationToken,
+) -> Result<(Vec<State>, usize)> {
+	let path = astar(
+		start,
+		|state| {
+			let cancelled = cancel.is_cancelled();
+			state
+				.successors_rev(blizzards)
+				.into_iter()
+				.filter(move |_| !cancelled)
+		},
+		|state| state.heuristic_rev(blizzards),
+		State::success_rev,
+	);
+	cancel.check()?;
+	path.ok_or_else(|| eyre!("no path to entrance"))
+}
+
+struct Day24;
+
+impl Puzzle for Day24 {
+	type Parsed = Array3<bool>;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		let blizzards = read_input(input)?;
+		lint::require(check_blizzards(&blizzards))?;
+		Ok(blizzards)
+	}
+
+	fn part_1(blizzards: &Self::Parsed) -> Result<Answer> {
+		let cancel = CancellationToken::current();
+		let (_, time_1) = there(blizzards, &State::new(), &cancel)?;
+		Ok(time_1.into())
+	}
+
+	fn part_2(blizzards: &Self::Parsed) -> Result<Answer> {
+		let cancel = CancellationToken::current();
+		let (path, time_1) = there(blizzards, &State::new(), &cancel)?;
+		let (path, time_2) = back_again(blizzards, path.last().unwrap(), &cancel)?;
+		let (_, time_3) = there(blizzards, path.last().unwrap(), &cancel)?;
+
+		let time_with_return = time_1 + time_2 + time_3;
+		Ok(time_with_return.into())
+	}
+
+	fn lint(input: &str) -> Result<Vec<Check>> {
+		let blizzards = read_input(input)?;
+		let mut checks = check_blizzards(&blizzards);
+		checks.push(check_crossing(&blizzards));
+		Ok(checks)
+	}
+
+	/// Generates a basin `size` tiles wide inside its walls and a fifth as high, with blizzards on
+	/// about half of the tiles and a way through.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		let (rows, columns) = ((size / 5).max(1), size.max(1));
+		let wall = |gap: usize| -> String {
+			(0..columns + 2)
+				.map(|column| if column == gap { '.' } else { '#' })
+				.chain(Some('\n'))
+				.collect()
+		};
+		loop {
+			let mut input = wall(1);
+			for _ in 0..rows {
+				input.push('#');
+				for column in 0..columns {
+					// only horizontal blizzards in the columns of the entrance and the exit
+					let symbols = if column == 0 || column + 1 == columns {
+						&b"<>"[..]
+					} else {
+						&WIND_SYMBOLS[..]
+					};
+					input.push(if rng.gen_bool(0.5) {
+						*symbols.choose(rng).expect("not empty") as char
+					} else {
+						'.'
+					});
+				}
+				input += "#\n";
+			}
+			input += &wall(columns);
+			if check_crossing(&read_input(&input)?).result.is_ok() {
+				return Ok(input);
+			}
+		}
+	}
+}
+
+solution!(Day24, day = 24, title = "Blizzard Basin");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day24::solve(EXAMPLE)?, Answers::new(18, 54));
+		Ok(())
+	}
+
+	#[test]
+	fn blocked_basin() -> Result<()> {
+		let checks = Day24::lint("#.####\n#<<<<#\n####.#\n")?;
+		assert!(checks[0].result.is_ok());
+		assert!(checks[1].result.is_err());
+		assert!(check_crossing(&read_input(EXAMPLE)?).result.is_ok());
+		Ok(())
+	}
+}
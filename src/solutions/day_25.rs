@@ -1,73 +1,129 @@
-use crate::try_for_each_line_in_file;
-use crate::SOLUTIONS;
-
-use color_eyre::eyre::{eyre, Result};
-use linkme::distributed_slice;
-
-trait Snafu: Copy {
-	fn from_snafu(snafu: &str) -> Result<Self>;
-	fn to_snafu(self) -> String;
-}
-
-impl Snafu for u64 {
-	fn from_snafu(snafu: &str) -> Result<Self> {
-		let mut result: u64 = 0;
-		for &byte in snafu.as_bytes() {
-			result = result
-				.checked_mul(5)
-				.ok_or_else(|| eyre!("overflow while parsing snafu"))?;
-			result = result
-				.checked_add_signed(match byte {
-					b'2' => 2,
-					b'1' => 1,
-					b'0' => 0,
-					b'-' => -1,
-					b'=' => -2,
-					_ => return Err(eyre!("invalid snafu number")),
-				})
-				.ok_or_else(|| eyre!("overflow while parsing snafu"))?;
-		}
-		Ok(result)
-	}
-
-	fn to_snafu(mut self) -> String {
-		let mut result = vec![];
-		while self != 0 {
-			let remainder = self % 5;
-			self /= 5;
-			let digit = match remainder {
-				0 => b'0',
-				1 => b'1',
-				2 => b'2',
-				3 => {
-					self += 1;
-					b'='
-				}
-				4 => {
-					self += 1;
-					b'-'
-				}
-				_ => unreachable!(),
-			};
-			result.push(digit);
-		}
-		result.reverse();
-		String::from_utf8(result).unwrap()
-	}
-}
-
-fn solution() -> Result<()> {
-	let mut sum = 0;
-	try_for_each_line_in_file("inputs/day-25", |line| {
-		let line = line.trim();
-		let converted: u64 = Snafu::from_snafu(line)?;
-		sum += converted;
-		Ok(())
-	})?;
-	let snafu_sum = sum.to_snafu();
-	println!("{snafu_sum}");
-	Ok(())
-}
-
-#[distributed_slice(SOLUTIONS)]
-static SOLUTION_DAY_25: (usize, fn() -> Result<()>) = (25, solution);
+use crate::generate::InputRng;
+use crate::try_for_each_line;
+use crate::{solution, Answer, Puzzle};
+
+use color_eyre::eyre::{eyre, Result};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+trait Snafu: Copy {
+	fn from_snafu(snafu: &str) -> Result<Self>;
+	fn to_snafu(self) -> String;
+}
+
+impl Snafu for u64 {
+	fn from_snafu(snafu: &str) -> Result<Self> {
+		let mut result: u64 = 0;
+		for &byte in snafu.as_bytes() {
+			result = result
+				.checked_mul(5)
+				.ok_or_else(|| eyre!("overflow while parsing snafu"))?;
+			result = result
+				.checked_add_signed(match byte {
+					b'2' => 2,
+					b'1' => 1,
+					b'0' => 0,
+					b'-' => -1,
+					b'=' => -2,
+					_ => return Err(eyre!("invalid snafu number")),
+				})
+				.ok_or_else(|| eyre!("overflow while parsing snafu"))?;
+		}
+		Ok(result)
+	}
+
+	fn to_snafu(mut self) -> String {
+		let mut result = vec![];
+		while self != 0 {
+			let remainder = self % 5;
+			self /= 5;
+			let digit = match remainder {
+				0 => b'0',
+				1 => b'1',
+				2 => b'2',
+				3 => {
+					self += 1;
+					b'='
+				}
+				4 => {
+					self += 1;
+					b'-'
+				}
+				_ => unreachable!(),
+			};
+			result.push(digit);
+		}
+		result.reverse();
+		String::from_utf8(result).unwrap()
+	}
+}
+
+struct Day25;
+
+impl Puzzle for Day25 {
+	type Parsed = Vec<u64>;
+
+	const HAS_PART_2: bool = false;
+
+	fn parse(input: &str) -> Result<Self::Parsed> {
+		let mut numbers = vec![];
+		try_for_each_line(input, |line| {
+			let line = line.trim();
+			numbers.push(Snafu::from_snafu(line)?);
+			Ok(())
+		})?;
+		Ok(numbers)
+	}
+
+	fn part_1(numbers: &Self::Parsed) -> Result<Answer> {
+		let snafu_sum = numbers.iter().sum::<u64>().to_snafu();
+		Ok(snafu_sum.into())
+	}
+
+	/// Generates `size` SNAFU numbers of up to 20 digits.
+	fn generate(rng: &mut InputRng, size: usize) -> Result<String> {
+		Ok((0..size.max(1))
+			.map(|_| {
+				let number: String = [*b"12".choose(rng).expect("not empty")]
+					.into_iter()
+					.chain(
+						(1..rng.gen_range(1..=20))
+							.map(|_| *b"=-012".choose(rng).expect("not empty")),
+					)
+					.map(char::from)
+					.collect();
+				format!("{number}\n")
+			})
+			.collect())
+	}
+}
+
+solution!(Day25, day = 25, title = "Full of Hot Air");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Answers;
+
+	const EXAMPLE: &str = "\
+1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122
+";
+
+	#[test]
+	fn example() -> Result<()> {
+		assert_eq!(Day25::solve(EXAMPLE)?, Answers::part1_only("2=-1=0"));
+		Ok(())
+	}
+}
@@ -0,0 +1,176 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use notify::{Event, RecursiveMode, Watcher};
+
+use crate::runner::{run, DayRun};
+use crate::{Answer, Solution};
+
+/// How long the files have to be left alone before they are read, since saving a file tends to
+/// write it in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Waits for changes to any of the files, and then for them to stop changing for a while.
+/// Returns which of the files changed.
+fn wait_for_changes(
+	events: &mpsc::Receiver<notify::Result<Event>>,
+	paths: &[PathBuf],
+) -> Result<Vec<bool>> {
+	let mut changed = vec![false; paths.len()];
+	let mut deadline = None;
+	loop {
+		let event = match deadline {
+			None => events.recv()?,
+			Some(deadline) => match events.recv_timeout(deadline - Instant::now()) {
+				Ok(event) => event,
+				Err(RecvTimeoutError::Timeout) => return Ok(changed),
+				Err(RecvTimeoutError::Disconnected) => return Err(eyre!("the watcher stopped")),
+			},
+		};
+		let event = event.wrap_err("failed to watch the files")?;
+		// reading the files shows up as well
+		if event.kind.is_access() || event.kind.is_other() {
+			continue;
+		}
+
+		let mut any = false;
+		for (changed, path) in changed.iter_mut().zip(paths) {
+			if event.paths.contains(path) {
+				*changed = true;
+				any = true;
+			}
+		}
+		if any {
+			deadline = Some(Instant::now() + DEBOUNCE);
+		}
+	}
+}
+
+fn format_answer(answer: &Answer) -> String {
+	let answer = answer.to_string();
+	if answer.contains('\n') {
+		format!("\n{answer}")
+	} else {
+		answer
+	}
+}
+
+/// Describes the outcome of a run, compared to the previous run on the same file.
+pub fn describe(previous: Option<&DayRun>, current: &DayRun) -> Vec<String> {
+	let time = match &current.parse {
+		Ok(time) => time,
+		Err(report) => return vec![format!("failed: {report:#}")],
+	};
+
+	let mut lines = vec![format!("parsed in {time:.1?}")];
+	for part in &current.parts {
+		let answer = match &part.answer {
+			Ok(answer) => answer,
+			Err(report) => {
+				lines.push(format!("part {} failed: {report:#}", part.part));
+				continue;
+			}
+		};
+		let previous = previous
+			.and_then(|previous| previous.part(part.part))
+			.and_then(|previous| previous.answer.as_ref().ok());
+		let change = match previous {
+			None => String::new(),
+			Some(previous) if previous == answer => " (unchanged)".to_string(),
+			Some(previous) => format!(" (was {})", format_answer(previous)),
+		};
+		lines.push(format!(
+			"part {} in {:.1?}: {}{change}",
+			part.part,
+			part.time,
+			format_answer(answer)
+		));
+	}
+	lines
+}
+
+fn run_file(
+	solution: &Solution,
+	path: &Path,
+	selected_part: Option<usize>,
+	timeout: Option<Duration>,
+) -> DayRun {
+	match fs::read_to_string(path).wrap_err_with(|| format!("failed to read {}", path.display())) {
+		Ok(input) => run(solution, &input, selected_part, timeout),
+		Err(report) => DayRun::failed(solution, report),
+	}
+}
+
+/// Runs the solution on every file, then again on each file that changes, until the process is
+/// interrupted. Failures are printed like answers, so a broken input just waits for the next
+/// change.
+pub fn watch(
+	solution: &Solution,
+	paths: &[PathBuf],
+	selected_part: Option<usize>,
+	timeout: Option<Duration>,
+) -> Result<()> {
+	let paths = paths
+		.iter()
+		.map(std::path::absolute)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let (sender, events) = mpsc::channel();
+	let mut watcher = notify::recommended_watcher(sender)?;
+	// editors often replace a file rather than write to it, which ends a watch on the file itself
+	let directories: BTreeSet<_> = paths.iter().filter_map(|path| path.parent()).collect();
+	for directory in directories {
+		watcher
+			.watch(directory, RecursiveMode::NonRecursive)
+			.wrap_err_with(|| format!("failed to watch {}", directory.display()))?;
+	}
+
+	let mut previous: Vec<Option<DayRun>> = paths.iter().map(|_| None).collect();
+	let mut changed = vec![true; paths.len()];
+	loop {
+		for ((path, previous), _) in paths
+			.iter()
+			.zip(&mut previous)
+			.zip(&changed)
+			.filter(|(_, changed)| **changed)
+		{
+			let current = run_file(solution, path, selected_part, timeout);
+			println!("{} {}:", solution.name(), path.display());
+			for line in describe(previous.as_ref(), &current) {
+				println!("  {line}");
+			}
+			println!();
+			*previous = Some(current);
+		}
+
+		changed = wait_for_changes(&events, &paths)?;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::find_solution;
+
+	#[test]
+	fn changes_are_described() -> Result<()> {
+		let solution = find_solution(2022, 6)?;
+		let first = run(solution, "bvwbjplbgvbhsrlpgdmjqwftvncz\n", None, None);
+		let second = run(solution, "nppdvjthqldpwncqszvftbrmjlhg\n", None, None);
+
+		let lines = describe(Some(&first), &second);
+		assert_eq!(lines.len(), 3);
+		assert!(lines[1].starts_with("part 1 in "));
+		assert!(lines[1].ends_with(": 6 (was 5)"));
+		assert!(lines[2].ends_with(": 23 (unchanged)"));
+
+		let broken = run(solution, "aaaa\n", None, None);
+		let lines = describe(Some(&second), &broken);
+		assert_eq!(lines[1], "part 1 failed: no marker found");
+		Ok(())
+	}
+}